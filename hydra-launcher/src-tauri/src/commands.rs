@@ -1,27 +1,77 @@
 use crate::config::HydraConfig;
-use crate::logger::{log_info, log_error, log_mcp_health, log_claude_interaction, log_system_metrics};
-use crate::mcp::health::{check_all_mcp_servers, McpHealthResult, McpStatus};
+use crate::config::layers::ConfigKeyProvenance;
+use crate::config::bundle::{self, BundleImportReport, BundleManifest};
+use crate::config::edit::ConfigBackup;
+use crate::config::profiles::{self, ProfileConfig, ProfileSummary};
+use crate::config::schema::{self, ConfigDiagnostic};
+use crate::config::watcher::{ConfigChangedEvent, ConfigKeyChange, ConfigStore};
+use crate::logger::{log_info, log_error, log_warn, log_claude_interaction, log_system_metrics};
+use crate::mcp::deps;
+use crate::mcp::health::{check_all_mcp_servers, McpHealthResult};
+use crate::mcp::history::{parse_range, HealthHistoryReport};
+use crate::mcp::inventory::{self, InventoryCache, McpInventory};
+use crate::mcp::logs as mcp_logs;
+use crate::mcp::monitor::HealthMonitor;
+use crate::mcp::ports::{self, PortCheck, PortReassignment};
+use crate::mcp::remediation::{RemediationStatus, Remediator};
+use crate::mcp::supervisor::{McpProcessInfo, Supervisor};
+use crate::mcp::tools::{self as mcp_tools, McpCallError, McpToolResult};
+use crate::paths::{hydra_root, HydraPaths};
 use crate::process::claude::spawn_claude_cli;
+use crate::process::metrics::{MetricsCollector, ProcessMetrics};
+use crate::process::registry::{registry, ChildRecord};
+use crate::process::session::ClaudeSession;
+use crate::process::shutdown::ShutdownReport;
+use crate::secrets::{self, vault::Vault, SecretSummary};
 use crate::process::ollama::{check_ollama_running, get_ollama_model_list};
 use serde::{Deserialize, Serialize};
 use sysinfo::System;
-use std::sync::Mutex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::process::Command;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 // Global state for YOLO mode
 pub struct AppState {
     pub yolo_enabled: Mutex<bool>,
+    pub config: Arc<ConfigStore>,
+    pub mcp: Arc<Supervisor>,
+    pub metrics: Arc<MetricsCollector>,
+    pub monitor: Arc<HealthMonitor>,
+    pub remediation: Arc<Remediator>,
+    pub inventory: Arc<InventoryCache>,
+    pub claude_session: Mutex<ClaudeSession>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        let config = Arc::new(ConfigStore::load());
         Self {
-            yolo_enabled: Mutex::new(true), // YOLO ON by default
+            // YOLO ON by default, unless the config or active profile says otherwise
+            yolo_enabled: Mutex::new(config.current().launcher.yolo_enabled),
+            config,
+            mcp: Arc::new(Supervisor::default()),
+            metrics: Arc::new(MetricsCollector::default()),
+            monitor: Arc::new(HealthMonitor::default()),
+            remediation: Arc::new(Remediator::default()),
+            inventory: Arc::new(InventoryCache::default()),
+            claude_session: Mutex::new(ClaudeSession::new()),
         }
     }
 }
 
+impl AppState {
+    /// Supervisor restart count of every MCP server
+    pub fn mcp_restarts(&self) -> BTreeMap<String, u32> {
+        self.mcp
+            .list(&self.config.current().mcp_tools)
+            .into_iter()
+            .map(|info| (info.name, info.restarts))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
     pub cpu_percent: f32,
@@ -30,21 +80,191 @@ pub struct SystemMetrics {
     pub memory_total_gb: f64,
 }
 
+/// Run every health check now; results feed the background monitor, which logs and emits
+/// `mcp-status-changed` only for transitions
 #[tauri::command]
-pub async fn check_mcp_health() -> Result<Vec<McpHealthResult>, String> {
-    log_info("MCP health check started");
-    let results = check_all_mcp_servers().await;
+pub async fn check_mcp_health(state: State<'_, AppState>) -> Result<Vec<McpHealthResult>, String> {
+    let config = state.config.current();
+    let results = check_all_mcp_servers(&config.mcp_tools, &state.mcp).await;
 
-    if let Ok(ref servers) = results {
-        for server in servers {
-            let status = if server.status == McpStatus::Online { "HEALTHY" } else { "DOWN" };
-            log_mcp_health(&server.name, status, server.response_time_ms);
+    if let Ok(ref results) = results {
+        for result in results {
+            if let Some(server) = config.mcp_tools.get(&result.name) {
+                state.monitor.record(server, result.clone(), config.launcher.health_history_days);
+            }
         }
     }
 
     results
 }
 
+/// Uptime %, latency percentiles and outages of an MCP server, `ollama` or `claude-cli`
+/// over the last `range` (`30m`, `24h`, `7d`)
+#[tauri::command]
+pub fn get_health_history(state: State<'_, AppState>, name: String, range: String) -> Result<HealthHistoryReport, String> {
+    Ok(state.monitor.history.report(&name, parse_range(&range)?))
+}
+
+/// Last known health of every enabled server, as tracked by the background monitor
+#[tauri::command]
+pub fn get_mcp_status(state: State<'_, AppState>) -> Vec<McpHealthResult> {
+    state.monitor.snapshot()
+}
+
+/// Failure counts and attempts of every target with a remediation rule that is currently down
+#[tauri::command]
+pub fn get_remediation_status(state: State<'_, AppState>) -> Vec<RemediationStatus> {
+    state.remediation.status()
+}
+
+/// Supervisor state of every configured MCP server
+#[tauri::command]
+pub fn list_mcp_processes(state: State<'_, AppState>) -> Vec<McpProcessInfo> {
+    state.mcp.list(&state.config.current().mcp_tools)
+}
+
+/// Start a server, after starting and waiting for whatever it `depends_on`
+#[tauri::command]
+pub async fn start_mcp(state: State<'_, AppState>, name: String) -> Result<McpProcessInfo, String> {
+    start_configured_mcp(&state, &name).await
+}
+
+#[tauri::command]
+pub async fn stop_mcp(state: State<'_, AppState>, name: String) -> Result<McpProcessInfo, String> {
+    let info = state.mcp.stop(&name).await?;
+    log_info(&format!("MCP server stopped: {}", name));
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn restart_mcp(state: State<'_, AppState>, name: String) -> Result<McpProcessInfo, String> {
+    if state.mcp.info(&name).is_active() {
+        state.mcp.stop(&name).await?;
+    }
+    start_configured_mcp(&state, &name).await
+}
+
+/// Tools, resources and prompts of each enabled server, from the cache unless `refresh` is set
+/// or a listable server has never been fetched. Flags `key_tools` the server no longer offers.
+#[tauri::command]
+pub async fn get_mcp_inventory(state: State<'_, AppState>, refresh: Option<bool>) -> Result<Vec<McpInventory>, String> {
+    let config = state.config.current();
+    for (name, server) in &config.mcp_tools {
+        let stale = refresh.unwrap_or(false) || state.inventory.get(name).is_none();
+        let channel = state.mcp.channel(name);
+        if stale && inventory::listable(server, channel.is_some()) {
+            state.inventory.refresh(name, server, channel).await;
+        }
+    }
+    Ok(state.inventory.list(&config.mcp_tools))
+}
+
+/// Run one tool on a server without a Claude session. `args` are checked against the tool's
+/// input schema first; `timeout_seconds` defaults to the server's `timeout_seconds`.
+#[tauri::command]
+pub async fn call_mcp_tool(
+    state: State<'_, AppState>,
+    server: String,
+    tool: String,
+    args: serde_json::Value,
+    timeout_seconds: Option<u64>,
+) -> Result<McpToolResult, McpCallError> {
+    let config = state.config.current();
+    let server_config = config
+        .mcp_tools
+        .get(&server)
+        .ok_or_else(|| McpCallError::UnknownServer { server: server.clone() })?;
+    let limit = std::time::Duration::from_secs(timeout_seconds.unwrap_or(server_config.timeout_seconds));
+
+    let result = mcp_tools::call_tool(&state.inventory, &server, server_config, state.mcp.channel(&server), &tool, args, limit).await;
+    match &result {
+        Ok(r) => log_info(&format!("MCP tool {}/{} completed in {}ms", server, tool, r.duration_ms)),
+        Err(e) => log_warn(&format!("MCP tool {}/{} failed: {}", server, tool, e)),
+    }
+    result
+}
+
+/// Which process listens on each configured MCP port, flagging ports held by something else
+#[tauri::command]
+pub async fn check_mcp_ports(state: State<'_, AppState>) -> Result<Vec<PortCheck>, String> {
+    let checks = ports::check_all_ports(&state.config.current().mcp_tools).await;
+    for check in checks.iter().filter(|c| c.message.is_some()) {
+        log_warn(&format!("MCP {}: {}", check.server, check.message.as_deref().unwrap_or_default()));
+    }
+    Ok(checks)
+}
+
+/// Move a server to the next free port, rewriting `health_port`, `health_url` and its args
+#[tauri::command]
+pub fn reassign_mcp_port(app: AppHandle, state: State<'_, AppState>, name: String) -> Result<PortReassignment, String> {
+    let config = state.config.current();
+    let server = config.mcp_tools.get(&name).ok_or_else(|| format!("Unknown MCP server: {}", name))?;
+    let old_port = server.health_port.ok_or_else(|| format!("MCP server has no health_port: {}", name))?;
+    let new_port = ports::find_free_port(&config.mcp_tools, old_port)
+        .ok_or_else(|| format!("No free port found above {}", old_port))?;
+
+    let changes = state.config.update(&ports::port_patch(&name, server, old_port, new_port))?;
+    notify_config_changed(&app, changes);
+    log_info(&format!("MCP server {} moved from port {} to {}", name, old_port, new_port));
+    Ok(PortReassignment {
+        server: name.clone(),
+        old_port,
+        new_port,
+        restart_required: state.mcp.info(&name).is_active(),
+    })
+}
+
+/// Last `lines` lines of a supervised server's captured stdout/stderr
+#[tauri::command]
+pub fn tail_mcp_log(name: String, lines: usize) -> Result<Vec<String>, String> {
+    mcp_logs::tail(&name, lines)
+}
+
+/// Children spawned by this launcher that are still tracked
+#[tauri::command]
+pub fn list_child_processes() -> Vec<ChildRecord> {
+    registry().children()
+}
+
+/// Processes left behind by a launcher run that crashed
+#[tauri::command]
+pub fn list_orphan_processes() -> Vec<ChildRecord> {
+    registry().orphans()
+}
+
+#[tauri::command]
+pub async fn reap_orphan_processes(pids: Vec<u32>) -> Result<Vec<ShutdownReport>, String> {
+    registry().reap(&pids).await
+}
+
+#[tauri::command]
+pub fn adopt_orphan_processes(pids: Vec<u32>) -> Result<Vec<ChildRecord>, String> {
+    registry().adopt(&pids)
+}
+
+/// Start an enabled server from the current config under the supervisor, dependencies first
+pub async fn start_configured_mcp(state: &AppState, name: &str) -> Result<McpProcessInfo, String> {
+    let config = state.config.current();
+    let mut results = deps::start_in_order(&state.mcp, &config, &[name.to_string()]).await?;
+    results.remove(name).unwrap_or_else(|| Err(format!("Unknown MCP server: {}", name)))
+}
+
+/// Start every enabled server in dependency order; used by `autostart_mcp`
+pub async fn start_all_mcp(state: &AppState) {
+    let config = state.config.current();
+    let names: Vec<String> = config.mcp_tools.iter().filter(|(_, s)| s.enabled).map(|(n, _)| n.clone()).collect();
+    match deps::start_in_order(&state.mcp, &config, &names).await {
+        Ok(results) => {
+            for (name, result) in results {
+                if let Err(e) = result {
+                    log_error(&format!("MCP autostart failed for {}: {}", name, e));
+                }
+            }
+        }
+        Err(e) => log_error(&format!("MCP autostart failed: {}", e)),
+    }
+}
+
 #[tauri::command]
 pub fn get_system_metrics() -> SystemMetrics {
     let mut sys = System::new_all();
@@ -65,24 +285,221 @@ pub fn get_system_metrics() -> SystemMetrics {
     }
 }
 
+/// CPU, memory, uptime, restarts and listening ports of every MCP server, Claude session
+/// and Ollama process tree; also streamed as `process-metrics`
 #[tauri::command]
-pub fn load_hydra_config() -> Result<HydraConfig, String> {
-    HydraConfig::load(None)
+pub async fn get_process_metrics(state: State<'_, AppState>) -> Result<Vec<ProcessMetrics>, String> {
+    let restarts = state.mcp_restarts();
+    let metrics = state.metrics.clone();
+    tokio::task::spawn_blocking(move || metrics.collect(&restarts))
+        .await
+        .map_err(|e| format!("Metrics collection failed: {}", e))
+}
+
+#[tauri::command]
+pub fn load_hydra_config(state: State<'_, AppState>) -> Result<HydraConfig, String> {
+    Ok(state.config.current())
+}
+
+/// Effective value of every config key and the layer that set it
+#[tauri::command]
+pub fn get_config_provenance(state: State<'_, AppState>) -> Result<Vec<ConfigKeyProvenance>, String> {
+    Ok(state.config.resolved().explain())
+}
+
+/// Why the config files last failed to load; the previous (or default) config stays active meanwhile
+#[tauri::command]
+pub fn get_config_error(state: State<'_, AppState>) -> Option<String> {
+    state.config.last_error()
+}
+
+/// Schema and consistency diagnostics for the config files
+#[tauri::command]
+pub fn validate_hydra_config(state: State<'_, AppState>) -> Vec<ConfigDiagnostic> {
+    schema::validate_layers(&state.config.resolved())
+}
+
+/// Apply an RFC 7396 merge patch to hydra-config.json
+#[tauri::command]
+pub fn update_hydra_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    patch: serde_json::Value,
+) -> Result<HydraConfig, String> {
+    let changes = state.config.update(&patch).map_err(|e| {
+        log_error(&format!("Config update failed: {}", e));
+        e
+    })?;
+    notify_config_changed(&app, changes);
+    Ok(state.config.current())
+}
+
+#[tauri::command]
+pub fn list_hydra_config_backups(state: State<'_, AppState>) -> Vec<ConfigBackup> {
+    state.config.backups()
+}
+
+#[tauri::command]
+pub fn restore_hydra_config_backup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<HydraConfig, String> {
+    let changes = state.config.restore_backup(&name)?;
+    log_info(&format!("Config restored from backup {}", name));
+    notify_config_changed(&app, changes);
+    Ok(state.config.current())
+}
+
+#[tauri::command]
+pub fn list_hydra_profiles(state: State<'_, AppState>) -> Vec<ProfileSummary> {
+    let config = state.config.current();
+    config
+        .profiles
+        .into_iter()
+        .map(|(name, profile)| ProfileSummary {
+            active: config.launcher.active_profile.as_deref() == Some(name.as_str()),
+            description: profile.description,
+            name,
+        })
+        .collect()
+}
+
+/// Activate a profile by name, or return to the base config with `None`
+#[tauri::command]
+pub fn activate_hydra_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: Option<String>,
+) -> Result<HydraConfig, String> {
+    if let Some(ref name) = name {
+        if !state.config.current().profiles.contains_key(name) {
+            return Err(format!("Profile not found: {}", name));
+        }
+    }
+
+    let patch = serde_json::json!({ "launcher": { "active_profile": name } });
+    let changes = state.config.update(&patch)?;
+    log_info(&format!("Profile activated: {}", name.as_deref().unwrap_or("(none)")));
+
+    let config = state.config.current();
+    *state.yolo_enabled.lock().unwrap() = config.launcher.yolo_enabled;
+    notify_config_changed(&app, changes);
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn create_hydra_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    profile: ProfileConfig,
+) -> Result<Vec<ProfileSummary>, String> {
+    profiles::validate_profile_name(&name)?;
+    if state.config.current().profiles.contains_key(&name) {
+        return Err(format!("Profile already exists: {}", name));
+    }
+
+    let patch = serde_json::json!({ "profiles": { &name: profile } });
+    let changes = state.config.update(&patch)?;
+    log_info(&format!("Profile created: {}", name));
+    notify_config_changed(&app, changes);
+    Ok(list_hydra_profiles(state))
+}
+
+fn notify_config_changed(app: &AppHandle, changes: Vec<ConfigKeyChange>) {
+    if !changes.is_empty() {
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        log_info(&format!("Config updated, changed: {}", keys.join(", ")));
+        let _ = app.emit("config-changed", ConfigChangedEvent { changes });
+    }
+}
+
+/// Pack the effective config (profiles included), MCP servers, AI handler config and prompt templates into a zip
+#[tauri::command]
+pub fn export_hydra_bundle(state: State<'_, AppState>, path: String) -> Result<BundleManifest, String> {
+    let effective = state.config.resolved().value;
+    let manifest = bundle::export_bundle(Path::new(&path), &effective, &HydraPaths::resolve())?;
+    log_info(&format!("Config bundle exported to {} ({} secrets stripped)", path, manifest.stripped_secrets.len()));
+    Ok(manifest)
+}
+
+/// Unpack a bundle over the local config; the replaced files are backed up first
+#[tauri::command]
+pub fn import_hydra_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<BundleImportReport, String> {
+    let report = bundle::import_bundle(Path::new(&path), &HydraPaths::resolve()).map_err(|e| {
+        log_error(&format!("Config bundle import failed: {}", e));
+        e
+    })?;
+    for warning in &report.warnings {
+        log_warn(&format!("Config bundle import: {}", warning));
+    }
+    log_info(&format!("Config bundle imported from {}", path));
+
+    let changes = state.config.reload()?;
+    *state.yolo_enabled.lock().unwrap() = state.config.current().launcher.yolo_enabled;
+    notify_config_changed(&app, changes);
+    Ok(report)
+}
+
+/// Resolved HYDRA config, data, log, cache and state directories
+#[tauri::command]
+pub fn get_hydra_paths() -> HydraPaths {
+    HydraPaths::resolve()
+}
+
+/// Vault secrets with masked values
+#[tauri::command]
+pub fn list_hydra_secrets() -> Result<Vec<SecretSummary>, String> {
+    let entries = Vault::open_default().entries()?;
+    Ok(entries.iter().map(|(name, entry)| SecretSummary::new(name, entry)).collect())
+}
+
+/// Store a new secret, referenced from the config as `secret://<name>`
+#[tauri::command]
+pub fn add_hydra_secret(name: String, value: String) -> Result<SecretSummary, String> {
+    secrets::validate_secret_name(&name)?;
+    let entry = Vault::open_default().add(&name, &value)?;
+    log_info(&format!("Secret added: {}", name));
+    Ok(SecretSummary::new(&name, &entry))
+}
+
+#[tauri::command]
+pub fn rotate_hydra_secret(name: String, value: String) -> Result<SecretSummary, String> {
+    secrets::validate_secret_name(&name)?;
+    let entry = Vault::open_default().rotate(&name, &value)?;
+    log_info(&format!("Secret rotated: {}", name));
+    Ok(SecretSummary::new(&name, &entry))
+}
+
+#[tauri::command]
+pub fn delete_hydra_secret(name: String) -> Result<(), String> {
+    secrets::validate_secret_name(&name)?;
+    Vault::open_default().delete(&name)?;
+    log_info(&format!("Secret deleted: {}", name));
+    Ok(())
 }
 
 #[tauri::command(rename_all = "camelCase")]
-pub async fn launch_claude(yolo_mode: bool) -> Result<String, String> {
-    spawn_claude_cli(yolo_mode).await
+pub async fn launch_claude(state: State<'_, AppState>, yolo_mode: bool) -> Result<String, String> {
+    let env = state.config.current().process_env()?;
+    spawn_claude_cli(yolo_mode, env).await
 }
 
 #[tauri::command]
-pub async fn check_ollama() -> Result<bool, String> {
-    check_ollama_running().await
+pub async fn check_ollama(state: State<'_, AppState>) -> Result<bool, String> {
+    let url = state.config.current().ollama_url();
+    check_ollama_running(&url).await
 }
 
 #[tauri::command]
-pub async fn get_ollama_models() -> Result<Vec<String>, String> {
-    get_ollama_model_list().await
+pub async fn get_ollama_models(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let url = state.config.current().ollama_url();
+    get_ollama_model_list(&url).await
 }
 
 #[tauri::command]
@@ -93,20 +510,17 @@ pub fn set_yolo_mode(state: State<'_, AppState>, enabled: bool) -> bool {
     enabled
 }
 
-/// Start a Claude session (placeholder - returns session ID)
+/// Start the launcher-owned Claude session unless one is already running; returns a session ID
 #[tauri::command(rename_all = "camelCase")]
-pub async fn start_claude_session(_yolo_mode: bool) -> Result<String, String> {
-    // For now, just verify Claude is available
-    #[cfg(windows)]
-    {
-        let output = Command::new("where")
-            .arg("claude")
-            .output()
-            .map_err(|e| format!("Failed to check claude: {}", e))?;
-
-        if !output.status.success() {
-            return Err("Claude CLI not found. Please install it first.".to_string());
-        }
+pub async fn start_claude_session(state: State<'_, AppState>, yolo_mode: bool) -> Result<String, String> {
+    let claude_path = find_claude_executable()?;
+    let hydra_path = hydra_root()?;
+    let env = state.config.current().process_env()?;
+
+    let mut session = state.claude_session.lock().unwrap();
+    if !session.is_running() {
+        session.start(&claude_path, yolo_mode, &hydra_path.display().to_string(), &env)?;
+        log_info(&format!("Claude session started ({})", claude_path));
     }
 
     Ok(format!("session_{}", std::time::SystemTime::now()
@@ -115,10 +529,30 @@ pub async fn start_claude_session(_yolo_mode: bool) -> Result<String, String> {
         .as_millis()))
 }
 
+/// Write a line to the running Claude session
+#[tauri::command]
+pub fn send_to_claude_session(state: State<'_, AppState>, message: String) -> Result<(), String> {
+    state.claude_session.lock().unwrap().send(&message)
+}
+
+/// Output the Claude session printed since the last read
+#[tauri::command]
+pub fn read_claude_session_output(state: State<'_, AppState>) -> Vec<String> {
+    state.claude_session.lock().unwrap().read_output()
+}
+
+#[tauri::command]
+pub fn stop_claude_session(state: State<'_, AppState>) -> Result<(), String> {
+    state.claude_session.lock().unwrap().stop()?;
+    log_info("Claude session stopped");
+    Ok(())
+}
+
 /// Send a message to Claude and get response
 #[tauri::command]
-pub async fn send_to_claude(message: String) -> Result<String, String> {
-    let hydra_path = get_hydra_path()?;
+pub async fn send_to_claude(state: State<'_, AppState>, message: String) -> Result<String, String> {
+    let hydra_path = hydra_root()?;
+    let env = state.config.current().process_env()?;
     let msg_preview = if message.len() > 100 {
         format!("{}...", &message[..100])
     } else {
@@ -134,6 +568,7 @@ pub async fn send_to_claude(message: String) -> Result<String, String> {
     // Note: Claude CLI doesn't have --cwd option, we use current_dir() instead
     let output = Command::new(&claude_path)
         .current_dir(&hydra_path)
+        .envs(&env)
         .args([
             "-p", &message,
             "--output-format", "text",
@@ -189,22 +624,3 @@ fn find_claude_executable() -> Result<String, String> {
 
     Err("Claude CLI not found. Please install it with: npm install -g @anthropic-ai/claude-code".to_string())
 }
-
-/// Get the HYDRA project path
-fn get_hydra_path() -> Result<String, String> {
-    if let Ok(path) = std::env::var("HYDRA_PATH") {
-        return Ok(path);
-    }
-
-    let home = std::env::var("USERPROFILE")
-        .or_else(|_| std::env::var("HOME"))
-        .map_err(|_| "Could not determine home directory")?;
-
-    let default_path = format!("{}\\Desktop\\ClaudeHYDRA", home);
-
-    if std::path::Path::new(&default_path).exists() {
-        Ok(default_path)
-    } else {
-        Err("HYDRA path not found".to_string())
-    }
-}
@@ -0,0 +1,364 @@
+use super::{edit, HydraConfig};
+use crate::paths::{legacy_dir, HydraPaths};
+use crate::secrets::SecretRef;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bumped when the archive layout changes
+pub const BUNDLE_FORMAT: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const CONFIG_ENTRY: &str = "hydra-config.json";
+const MCP_SERVERS_ENTRY: &str = "mcp-servers.json";
+const AI_CONFIG_ENTRY: &str = "ai-config.json";
+const PROMPTS_DIR: &str = "prompts/";
+
+/// Key name fragments that mark a value as a credential
+const SECRET_KEY_HINTS: &[&str] = &["key", "token", "secret", "password", "passwd", "credential"];
+/// Well-known credential prefixes, caught even under innocuous key names
+const SECRET_VALUE_PREFIXES: &[&str] = &["sk-", "ghp_", "github_pat_", "xoxb-", "xoxp-", "AIza"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub launcher_version: String,
+    pub created_at: String,
+    /// Archive entries besides the manifest
+    pub files: Vec<String>,
+    /// Keys whose values were replaced by `secret://` references
+    pub stripped_secrets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImportReport {
+    pub written: Vec<String>,
+    /// Secrets the bundle expects in the local vault
+    pub required_secrets: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Machine-specific directories and the variables they become in a bundle
+struct PathVars(Vec<(&'static str, PathBuf)>);
+
+impl PathVars {
+    fn new(paths: &HydraPaths) -> Self {
+        let root = paths.root.clone().or_else(legacy_dir);
+        let vars = [("HYDRA_ROOT", root), ("HOME", dirs::home_dir())];
+        // Most specific first, so the root wins over the home dir it lives in
+        let mut vars: Vec<_> = vars.into_iter().filter_map(|(name, p)| p.map(|p| (name, p))).collect();
+        vars.sort_by_key(|(_, p)| std::cmp::Reverse(p.as_os_str().len()));
+        Self(vars)
+    }
+
+    /// Replace local paths with `${VAR}`
+    fn to_portable(&self, s: &str) -> String {
+        let mut out = s.to_string();
+        for (name, path) in &self.0 {
+            let local = path.display().to_string();
+            for form in [local.clone(), local.replace('\\', "/")] {
+                if !form.is_empty() {
+                    out = replace_ignore_case(&out, &form, &format!("${{{}}}", name));
+                }
+            }
+        }
+        out
+    }
+
+    /// Replace `${VAR}` with local paths, converting the separators of the path that follows
+    fn to_local(&self, s: &str) -> String {
+        let mut out = s.to_string();
+        for (name, path) in &self.0 {
+            let var = format!("${{{}}}", name);
+            while let Some(start) = out.find(&var) {
+                let rest = &out[start + var.len()..];
+                let tail_len = rest.find(|c: char| c.is_whitespace() || c == '"' || c == '\'').unwrap_or(rest.len());
+                let tail: String = rest[..tail_len]
+                    .chars()
+                    .map(|c| if c == '/' || c == '\\' { std::path::MAIN_SEPARATOR } else { c })
+                    .collect();
+                out = format!("{}{}{}{}", &out[..start], path.display(), tail, &rest[tail_len..]);
+            }
+        }
+        out
+    }
+}
+
+fn replace_ignore_case(haystack: &str, needle: &str, replacement: &str) -> String {
+    let lower = haystack.to_ascii_lowercase();
+    let needle_lower = needle.to_ascii_lowercase();
+    let mut out = String::with_capacity(haystack.len());
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find(&needle_lower) {
+        out.push_str(&haystack[pos..pos + found]);
+        out.push_str(replacement);
+        pos += found + needle.len();
+    }
+    out.push_str(&haystack[pos..]);
+    out
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    // `apiKeyEnv` style keys name a variable rather than hold a credential
+    !key.ends_with("env") && SECRET_KEY_HINTS.iter().any(|hint| key.contains(hint))
+}
+
+/// Vault name for a stripped credential, from its full key path so `API_KEY` under
+/// different servers gets different secrets
+fn secret_name(scope: &str, path: &str) -> String {
+    let name = path.to_ascii_lowercase().replace('_', "-").replace('[', ".").replace(']', "");
+    if scope.is_empty() {
+        name
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+/// Rewrite paths and strip credentials from every string in `value`; `scope` prefixes secret names
+fn make_portable(value: &mut Value, scope: &str, path: &str, vars: &PathVars, stripped: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                if let Value::String(s) = v {
+                    let is_plain = !s.is_empty() && SecretRef::parse(s).is_none() && !s.starts_with("${");
+                    if is_plain && (is_secret_key(key) || SECRET_VALUE_PREFIXES.iter().any(|p| s.starts_with(p))) {
+                        *s = format!("secret://{}", secret_name(scope, &child));
+                        stripped.push(child);
+                        continue;
+                    }
+                }
+                make_portable(v, scope, &child, vars, stripped);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                make_portable(item, scope, &format!("{}[{}]", path, i), vars, stripped);
+            }
+        }
+        Value::String(s) => *s = vars.to_portable(s),
+        _ => {}
+    }
+}
+
+fn make_local(value: &mut Value, vars: &PathVars) {
+    match value {
+        Value::Object(map) => map.values_mut().for_each(|v| make_local(v, vars)),
+        Value::Array(items) => items.iter_mut().for_each(|v| make_local(v, vars)),
+        Value::String(s) => *s = vars.to_local(s),
+        _ => {}
+    }
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    // PowerShell writes JSON with a BOM
+    serde_json::from_str(content.trim_start_matches('\u{feff}')).map_err(|e| format!("Invalid JSON in {}: {}", path.display(), e))
+}
+
+fn to_pretty(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize bundle entry: {}", e))
+}
+
+/// Slash-command prompt templates used by Claude CLI in the HYDRA root
+fn prompts_dir(root: &Path) -> PathBuf {
+    root.join(".claude").join("commands")
+}
+
+/// Pack the effective config, MCP server list, AI handler config and prompt templates
+pub fn export_bundle(dest: &Path, config: &Value, paths: &HydraPaths) -> Result<BundleManifest, String> {
+    let vars = PathVars::new(paths);
+    let mut stripped = Vec::new();
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    let mut config = config.clone();
+    make_portable(&mut config, "", "", &vars, &mut stripped);
+    entries.push((CONFIG_ENTRY.to_string(), to_pretty(&config)?));
+
+    if let Some(root) = &paths.root {
+        for (entry, file) in [
+            (MCP_SERVERS_ENTRY, root.join("mcp-servers.json")),
+            (AI_CONFIG_ENTRY, root.join("ai-handler").join("ai-config.json")),
+        ] {
+            if file.is_file() {
+                let mut value = read_json(&file)?;
+                let mut entry_stripped = Vec::new();
+                let scope = entry.trim_end_matches(".json");
+                make_portable(&mut value, scope, "", &vars, &mut entry_stripped);
+                stripped.extend(entry_stripped.into_iter().map(|key| format!("{}:{}", entry, key)));
+                entries.push((entry.to_string(), to_pretty(&value)?));
+            }
+        }
+
+        if let Ok(prompts) = fs::read_dir(prompts_dir(root)) {
+            for prompt in prompts.filter_map(Result::ok).filter(|e| e.path().extension().is_some_and(|x| x == "md")) {
+                let content = fs::read_to_string(prompt.path()).map_err(|e| format!("Failed to read prompt: {}", e))?;
+                let name = prompt.file_name().to_string_lossy().to_string();
+                entries.push((format!("{}{}", PROMPTS_DIR, name), vars.to_portable(&content)));
+            }
+        }
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Local::now().to_rfc3339(),
+        files: entries.iter().map(|(name, _)| name.clone()).collect(),
+        stripped_secrets: stripped,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+
+    let write = || -> zip::result::ZipResult<()> {
+        let mut zip = ZipWriter::new(File::create(dest)?);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, content) in std::iter::once((MANIFEST, &manifest_json)).chain(entries.iter().map(|(n, c)| (n.as_str(), c))) {
+            zip.start_file(name, options)?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish()?;
+        Ok(())
+    };
+    write().map_err(|e| format!("Failed to write bundle {}: {}", dest.display(), e))?;
+    Ok(manifest)
+}
+
+/// Unpack a bundle: the config replaces the user config (with a backup), the rest goes into the HYDRA root
+pub fn import_bundle(src: &Path, paths: &HydraPaths) -> Result<BundleImportReport, String> {
+    let file = File::open(src).map_err(|e| format!("Failed to open bundle {}: {}", src.display(), e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a HYDRA bundle: {}", e))?;
+    let mut read_entry = |name: &str| -> Result<String, String> {
+        let mut entry = archive.by_name(name).map_err(|e| format!("Bundle entry {}: {}", name, e))?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content).map_err(|e| format!("Bundle entry {}: {}", name, e))?;
+        Ok(content)
+    };
+
+    let manifest: BundleManifest = serde_json::from_str(&read_entry(MANIFEST)?)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+    if manifest.format > BUNDLE_FORMAT {
+        return Err(format!(
+            "Bundle format {} is newer than supported format {}; update the launcher",
+            manifest.format, BUNDLE_FORMAT
+        ));
+    }
+
+    let vars = PathVars::new(paths);
+    let mut report = BundleImportReport {
+        written: Vec::new(),
+        required_secrets: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for stripped in &manifest.stripped_secrets {
+        let name = match stripped.split_once(':') {
+            Some((entry, key)) => secret_name(entry.trim_end_matches(".json"), key),
+            None => secret_name("", stripped),
+        };
+        if !report.required_secrets.contains(&name) {
+            report.required_secrets.push(name);
+        }
+    }
+
+    // Validate everything before touching the disk
+    let mut config: Value = serde_json::from_str(&read_entry(CONFIG_ENTRY)?)
+        .map_err(|e| format!("Invalid {} in bundle: {}", CONFIG_ENTRY, e))?;
+    make_local(&mut config, &vars);
+    let config_json = to_pretty(&config)?;
+    HydraConfig::parse(&config_json).map_err(|e| format!("Bundled config rejected: {}", e))?;
+
+    let mut extra = Vec::new();
+    for name in manifest.files.iter().filter(|n| n.as_str() != CONFIG_ENTRY) {
+        let target = match (name.as_str(), &paths.root) {
+            (_, None) => {
+                report.warnings.push(format!("Skipped {}: HYDRA root not found, set HYDRA_PATH", name));
+                continue;
+            }
+            (MCP_SERVERS_ENTRY, Some(root)) => root.join("mcp-servers.json"),
+            (AI_CONFIG_ENTRY, Some(root)) => root.join("ai-handler").join("ai-config.json"),
+            (prompt, Some(root)) => match prompt.strip_prefix(PROMPTS_DIR).filter(|p| !p.contains(['/', '\\']) && !p.starts_with('.')) {
+                Some(file_name) => prompts_dir(root).join(file_name),
+                None => {
+                    report.warnings.push(format!("Skipped unexpected bundle entry {}", prompt));
+                    continue;
+                }
+            },
+        };
+
+        let content = read_entry(name)?;
+        let content = if name.ends_with(".json") {
+            let mut value: Value = serde_json::from_str(&content).map_err(|e| format!("Invalid {} in bundle: {}", name, e))?;
+            make_local(&mut value, &vars);
+            to_pretty(&value)?
+        } else {
+            vars.to_local(&content)
+        };
+        extra.push((target, content));
+    }
+
+    let config_path = paths.config_file();
+    edit::replace_config_file(&config_path, &config_json)?;
+    report.written.push(config_path.display().to_string());
+    for (target, content) in extra {
+        if target.extension().is_some_and(|ext| ext == "json") {
+            edit::replace_config_file(&target, &content)?;
+        } else {
+            edit::write_atomic(&target, &content)?;
+        }
+        report.written.push(target.display().to_string());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bundle_round_trip_strips_secrets_and_paths() {
+        let dir = std::env::temp_dir().join(format!("hydra-bundle-{}", std::process::id()));
+        let root = dir.join("ClaudeHYDRA");
+        fs::create_dir_all(prompts_dir(&root)).unwrap();
+        fs::write(prompts_dir(&root).join("hydra.md"), format!("Run {}/scripts", root.display())).unwrap();
+        let paths = HydraPaths {
+            root: Some(root.clone()),
+            config_dir: dir.join("config"),
+            data_dir: dir.join("data"),
+            log_dir: dir.join("logs"),
+            cache_dir: dir.join("cache"),
+            state_dir: dir.join("state"),
+        };
+
+        let handler = format!("{}/ai-handler", root.display());
+        let config = json!({
+            "env": {"AI_HANDLER_PATH": handler, "ANTHROPIC_API_KEY": "sk-ant-live", "OPENAI_TOKEN": "secret://openai"},
+        });
+        let bundle = dir.join("team.hydra.zip");
+        let manifest = export_bundle(&bundle, &config, &paths).unwrap();
+        assert_eq!(manifest.stripped_secrets, vec!["env.ANTHROPIC_API_KEY"]);
+        assert!(manifest.files.contains(&"prompts/hydra.md".to_string()));
+
+        let mut archive = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+        let mut exported = String::new();
+        archive.by_name(CONFIG_ENTRY).unwrap().read_to_string(&mut exported).unwrap();
+        assert!(exported.contains("${HYDRA_ROOT}/ai-handler") && !exported.contains("sk-ant-live"), "{}", exported);
+
+        let report = import_bundle(&bundle, &paths).unwrap();
+        let imported = HydraConfig::parse(&fs::read_to_string(paths.config_file()).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(report.required_secrets, vec!["env.anthropic-api-key"]);
+        assert_eq!(PathBuf::from(&imported.env["AI_HANDLER_PATH"]), root.join("ai-handler"));
+        assert_eq!(imported.env["ANTHROPIC_API_KEY"], "secret://env.anthropic-api-key");
+
+        let mut servers = json!({"mcp_tools": {"a": {"env": {"API_KEY": "k1"}}, "b": {"env": {"API_KEY": "k2"}}}});
+        make_portable(&mut servers, "", "", &PathVars::new(&paths), &mut Vec::new());
+        assert_eq!(servers["mcp_tools"]["a"]["env"]["API_KEY"], "secret://mcp-tools.a.env.api-key");
+        assert_eq!(servers["mcp_tools"]["b"]["env"]["API_KEY"], "secret://mcp-tools.b.env.api-key");
+    }
+}
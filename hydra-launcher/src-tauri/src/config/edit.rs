@@ -0,0 +1,200 @@
+use super::layers::{self, LayerSources};
+use super::{migrate, HydraConfig};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Number of timestamped backups kept next to the config file
+pub const MAX_CONFIG_BACKUPS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Apply an RFC 7396 JSON merge patch in place
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let target = target.as_object_mut().expect("target is an object");
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Parse `content` as the file at `path` and resolve the full layer stack with it, so
+/// `${VAR}` references to another layer's `env` are accepted
+fn check_with_layers(sources: &LayerSources, path: &Path, content: &str) -> Result<HydraConfig, String> {
+    let document: Value = serde_json::from_str(content).map_err(|e| format!("invalid JSON: {}", e))?;
+    layers::resolve_substituted(sources, path, &document).map(|resolved| resolved.config)
+}
+
+/// Patch the config file at `path`, one of the layers of `sources`, validating the result
+/// against every layer before it is written
+pub fn update_config_file(sources: &LayerSources, path: &Path, patch: &Value) -> Result<HydraConfig, String> {
+    let mut document = if path.exists() {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read config {}: {}", path.display(), e))?;
+        let mut value: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to load config {}: invalid JSON: {}", path.display(), e))?;
+        migrate::migrate(&mut value)?;
+        value
+    } else {
+        Value::Object(Map::new())
+    };
+
+    merge_patch(&mut document, patch);
+    let content = serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let config = check_with_layers(sources, path, &content).map_err(|e| format!("Patch rejected: {}", e))?;
+
+    replace_config_file(path, &content)?;
+    Ok(config)
+}
+
+/// Replace the config file with a backup; the current file is backed up first
+pub fn restore_backup(sources: &LayerSources, path: &Path, name: &str) -> Result<HydraConfig, String> {
+    let backup = backup_path(path, name)?;
+    let content = fs::read_to_string(&backup).map_err(|e| format!("Failed to read backup {}: {}", name, e))?;
+    let config = check_with_layers(sources, path, &content).map_err(|e| format!("Backup {} is invalid: {}", name, e))?;
+
+    replace_config_file(path, &content)?;
+    Ok(config)
+}
+
+/// Back up the current file, then atomically replace it with `content`
+pub fn replace_config_file(path: &Path, content: &str) -> Result<(), String> {
+    backup_config(path)?;
+    write_atomic(path, content)
+}
+
+/// Write via a temp file in the same directory, then rename over the target
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let dir = path.parent().ok_or_else(|| format!("Invalid config path: {}", path.display()))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("hydra-config.json");
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let result = File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write config {}: {}", path.display(), e)
+    })
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new(".")).join("config-backups")
+}
+
+fn backup_path(path: &Path, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid backup name: {}", name));
+    }
+    let backup = backup_dir(path).join(name);
+    if backup.is_file() {
+        Ok(backup)
+    } else {
+        Err(format!("Backup not found: {}", name))
+    }
+}
+
+/// Copy the current config into the backup directory and prune old backups
+fn backup_config(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let dir = backup_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup dir: {}", e))?;
+
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("hydra-config");
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S-%6f");
+    let backup = dir.join(format!("{}.{}.json", stem, timestamp));
+    fs::copy(path, &backup).map_err(|e| format!("Failed to back up config: {}", e))?;
+
+    for old in list_backups(path).into_iter().skip(MAX_CONFIG_BACKUPS) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(())
+}
+
+/// Backups for the config at `path`, newest first
+pub fn list_backups(path: &Path) -> Vec<ConfigBackup> {
+    let Ok(entries) = fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<ConfigBackup> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .map(|e| ConfigBackup {
+            name: e.file_name().to_string_lossy().to_string(),
+            path: e.path().display().to_string(),
+            size_bytes: e.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .collect();
+
+    // Timestamped names sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch_rfc7396() {
+        let mut doc = json!({"a": "b", "c": {"d": "e", "f": "g"}, "list": [1, 2]});
+        merge_patch(&mut doc, &json!({"a": "z", "c": {"f": null}, "list": [3]}));
+        assert_eq!(doc, json!({"a": "z", "c": {"d": "e"}, "list": [3]}));
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_and_rotates_backups() {
+        let dir = std::env::temp_dir().join(format!("hydra-edit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hydra-config.json");
+        fs::write(&path, r#"{"version": "10.5.0", "logging": {"level": "info"}}"#).unwrap();
+        let sources = LayerSources { user: Some(path.clone()), project: None, vars: Vec::new() };
+
+        let err = update_config_file(&sources, &path, &json!({"logging": {"rotate_count": "many"}})).unwrap_err();
+        assert!(err.contains("logging.rotate_count"), "{}", err);
+        assert!(list_backups(&path).is_empty());
+
+        for level in ["debug", "warn"] {
+            let config = update_config_file(&sources, &path, &json!({"logging": {"level": level}})).unwrap();
+            assert_eq!(config.logging.level, level);
+        }
+        let backups = list_backups(&path);
+        assert_eq!(backups.len(), 2);
+
+        let restored = restore_backup(&sources, &path, &backups[1].name).unwrap();
+        assert_eq!(restored.logging.level, "info");
+        assert!(restore_backup(&sources, &path, "../hydra-config.json").is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,161 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Expand `${VAR}` references in every string of a config document.
+///
+/// Variables come from the document's `env` block (which may reference itself),
+/// falling back to `process_env`. `$${` is an escaped literal `${`; `${...}` around anything
+/// but a variable name is left as written.
+pub fn interpolate(value: &mut Value, process_env: impl Fn(&str) -> Option<String>) -> Result<(), String> {
+    let raw: BTreeMap<String, String> = value
+        .get("env")
+        .and_then(Value::as_object)
+        .map(|env| {
+            env.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut resolver = Resolver {
+        raw: &raw,
+        process_env: &process_env,
+        resolved: BTreeMap::new(),
+        stack: Vec::new(),
+    };
+    for name in raw.keys() {
+        resolver.resolve_var(name)?;
+    }
+
+    walk(value, "", &mut resolver)
+}
+
+struct Resolver<'a, F: Fn(&str) -> Option<String>> {
+    raw: &'a BTreeMap<String, String>,
+    process_env: &'a F,
+    resolved: BTreeMap<String, String>,
+    stack: Vec<String>,
+}
+
+impl<F: Fn(&str) -> Option<String>> Resolver<'_, F> {
+    fn resolve_var(&mut self, name: &str) -> Result<Option<String>, String> {
+        if let Some(value) = self.resolved.get(name) {
+            return Ok(Some(value.clone()));
+        }
+
+        let Some(raw) = self.raw.get(name) else {
+            return Ok((self.process_env)(name));
+        };
+
+        if let Some(start) = self.stack.iter().position(|n| n == name) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(format!("variable reference cycle: {}", cycle.join(" -> ")));
+        }
+
+        self.stack.push(name.to_string());
+        let expanded = self.expand(raw, &format!("env.{}", name));
+        self.stack.pop();
+
+        let expanded = expanded?;
+        self.resolved.insert(name.to_string(), expanded.clone());
+        Ok(Some(expanded))
+    }
+
+    fn expand(&mut self, input: &str, path: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+
+        while let Some(pos) = rest.find('$') {
+            out.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+
+            if rest.starts_with("$${") {
+                out.push_str("${");
+                rest = &rest[3..];
+            } else if let Some(body) = rest.strip_prefix("${") {
+                let end = body
+                    .find('}')
+                    .ok_or_else(|| format!("unterminated `${{` at `{}`", path))?;
+                let name = &body[..end];
+                // Shell text such as PowerShell `${env:FOO}` isn't ours to expand
+                if !is_var_name(name) {
+                    out.push_str(&rest[..end + 3]);
+                    rest = &body[end + 1..];
+                    continue;
+                }
+                let value = self
+                    .resolve_var(name)?
+                    .ok_or_else(|| format!("unknown variable `${{{}}}` at `{}`", name, path))?;
+                out.push_str(&value);
+                rest = &body[end + 1..];
+            } else {
+                out.push('$');
+                rest = &rest[1..];
+            }
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn walk<F: Fn(&str) -> Option<String>>(value: &mut Value, path: &str, resolver: &mut Resolver<'_, F>) -> Result<(), String> {
+    match value {
+        Value::String(s) if s.contains('$') => {
+            *s = resolver.expand(s, path)?;
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                walk(item, &format!("{}[{}]", path, i), resolver)?;
+            }
+        }
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                let key = if path.is_empty() { k.clone() } else { format!("{}.{}", path, k) };
+                walk(v, &key, resolver)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_nested_and_process_env() {
+        let mut doc = json!({
+            "env": {"ROOT": "${HOME_DIR}/hydra", "HANDLER": "${ROOT}/ai-handler"},
+            "mcp_tools": {"serena": {"args": ["--project", "${ROOT}", "$args", "$${LITERAL}", "${env:APPDATA}"]}}
+        });
+        interpolate(&mut doc, |name| (name == "HOME_DIR").then(|| "/home/me".to_string())).unwrap();
+
+        assert_eq!(doc["env"]["HANDLER"], "/home/me/hydra/ai-handler");
+        assert_eq!(doc["mcp_tools"]["serena"]["args"], json!(["--project", "/home/me/hydra", "$args", "${LITERAL}", "${env:APPDATA}"]));
+    }
+
+    #[test]
+    fn test_cycles_and_unknown_variables() {
+        let mut doc = json!({"env": {"A": "${B}", "B": "${A}"}});
+        let err = interpolate(&mut doc, no_env).unwrap_err();
+        assert!(err.contains("A -> B -> A"), "{}", err);
+
+        let mut doc = json!({"commands": {"ai": {"command": "${MISSING}\\Invoke-AI.ps1"}}});
+        let err = interpolate(&mut doc, no_env).unwrap_err();
+        assert_eq!(err, "unknown variable `${MISSING}` at `commands.ai.command`");
+    }
+}
@@ -0,0 +1,383 @@
+use super::{interpolate, migrate, profiles, HydraConfig};
+use crate::paths::HydraPaths;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix for environment overrides, e.g. `HYDRA_LOGGING__LEVEL=debug`
+const ENV_PREFIX: &str = "HYDRA_";
+
+/// Separator between key segments in environment override names
+const ENV_SEPARATOR: &str = "__";
+
+/// Configuration layers, lowest precedence first
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigLayer {
+    Default,
+    User,
+    Project,
+    Profile,
+    Env,
+}
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValueSource {
+    pub layer: ConfigLayer,
+    /// File path, "profile:<name>", environment variable name, or "built-in"
+    pub origin: String,
+}
+
+/// Effective value of a single config key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigKeyProvenance {
+    pub key: String,
+    pub value: Value,
+    pub layer: ConfigLayer,
+    pub origin: String,
+}
+
+/// Result of merging every configuration layer
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: HydraConfig,
+    pub value: Value,
+    pub provenance: BTreeMap<String, ValueSource>,
+}
+
+impl ResolvedConfig {
+    /// Flatten the merged config into one entry per leaf key
+    pub fn explain(&self) -> Vec<ConfigKeyProvenance> {
+        self.provenance
+            .iter()
+            .map(|(key, source)| ConfigKeyProvenance {
+                key: key.clone(),
+                value: lookup(&self.value, key).cloned().unwrap_or(Value::Null),
+                layer: source.layer,
+                origin: source.origin.clone(),
+            })
+            .collect()
+    }
+}
+
+/// User-level config file
+pub fn user_config_path() -> PathBuf {
+    HydraPaths::resolve().config_file()
+}
+
+/// Where the project config search starts: the working directory, then the HYDRA root
+pub fn project_search_roots() -> Vec<PathBuf> {
+    std::env::current_dir().ok().into_iter().chain(HydraPaths::resolve().root).collect()
+}
+
+/// Project config for the working directory, else for the HYDRA root
+pub fn project_config_path() -> Option<PathBuf> {
+    project_search_roots().iter().find_map(|dir| find_project_config(dir))
+}
+
+/// Find `.hydra/config` (or `.hydra/config.json`) in `start` or its ancestors
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        ["config", "config.json"]
+            .iter()
+            .map(|name| dir.join(".hydra").join(name))
+            .find(|p| p.is_file())
+    })
+}
+
+/// Existing user and project config files, lowest precedence first
+pub fn config_files() -> Vec<PathBuf> {
+    std::iter::once(user_config_path())
+        .chain(project_config_path())
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Resolve the effective config: defaults, user file, project file, active profile, then HYDRA_* env vars
+pub fn resolve() -> Result<ResolvedConfig, String> {
+    resolve_from(Some(user_config_path()), project_config_path(), std::env::vars())
+}
+
+pub fn resolve_from(
+    user: Option<PathBuf>,
+    project: Option<PathBuf>,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Result<ResolvedConfig, String> {
+    let mut files = Vec::new();
+    for (layer, path) in [(ConfigLayer::User, user), (ConfigLayer::Project, project)] {
+        if let Some(path) = path.filter(|p| p.is_file()) {
+            files.push((layer, path.display().to_string(), read_layer(&path)?));
+        }
+    }
+    resolve_layers(files, vars)
+}
+
+/// The file layers and environment a resolution reads
+pub struct LayerSources {
+    pub user: Option<PathBuf>,
+    pub project: Option<PathBuf>,
+    pub vars: Vec<(String, String)>,
+}
+
+impl LayerSources {
+    /// The real user and project config files and the process environment
+    pub fn current() -> Self {
+        Self { user: Some(user_config_path()), project: project_config_path(), vars: std::env::vars().collect() }
+    }
+}
+
+/// Resolve every layer of `sources` as if the file at `path`, one of its layers, contained
+/// `document`, so an edit can be checked against the whole stack before it is written
+pub fn resolve_substituted(sources: &LayerSources, path: &Path, document: &Value) -> Result<ResolvedConfig, String> {
+    let mut document = document.clone();
+    migrate::migrate(&mut document)?;
+
+    let mut files = Vec::new();
+    let mut substituted = false;
+    for (layer, source) in [(ConfigLayer::User, &sources.user), (ConfigLayer::Project, &sources.project)] {
+        match source {
+            Some(source) if source == path => {
+                files.push((layer, source.display().to_string(), document.clone()));
+                substituted = true;
+            }
+            Some(source) if source.is_file() => files.push((layer, source.display().to_string(), read_layer(source)?)),
+            _ => {}
+        }
+    }
+    if !substituted {
+        return Err(format!("{} is not a config layer", path.display()));
+    }
+    resolve_layers(files, sources.vars.iter().cloned())
+}
+
+/// Merge already-read file layers (lowest precedence first) over the defaults, then the
+/// active profile and HYDRA_* env vars
+fn resolve_layers(
+    files: Vec<(ConfigLayer, String, Value)>,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Result<ResolvedConfig, String> {
+    let mut value = serde_json::to_value(HydraConfig::default())
+        .map_err(|e| format!("Failed to serialize defaults: {}", e))?;
+    let mut provenance = BTreeMap::new();
+    record_leaves(
+        &value,
+        "",
+        &ValueSource { layer: ConfigLayer::Default, origin: "built-in".to_string() },
+        &mut provenance,
+    );
+
+    for (layer, origin, overlay) in files {
+        merge(&mut value, overlay, "", &ValueSource { layer, origin }, &mut provenance);
+    }
+
+    if let Some((name, overlay)) = profiles::active_overlay(&value)? {
+        let source = ValueSource { layer: ConfigLayer::Profile, origin: format!("profile:{}", name) };
+        merge(&mut value, overlay, "", &source, &mut provenance);
+    }
+
+    let vars: Vec<(String, String)> = vars.collect();
+    for (name, raw) in &vars {
+        if let Some((key, overlay)) = env_override(&value, name, raw) {
+            crate::logger::log_debug(&format!("Config override {} -> {}", name, key));
+            let source = ValueSource { layer: ConfigLayer::Env, origin: name.clone() };
+            merge(&mut value, overlay, "", &source, &mut provenance);
+        }
+    }
+
+    interpolate::interpolate(&mut value, |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone()))?;
+
+    let config = serde_path_to_error::deserialize(value.clone()).map_err(|err| {
+        let path = err.path().to_string();
+        match provenance.get(&path) {
+            Some(source) => format!(
+                "invalid value at `{}` (set by {:?} layer, {}): {}",
+                path, source.layer, source.origin, err.into_inner()
+            ),
+            None => format!("invalid value at `{}`: {}", path, err.into_inner()),
+        }
+    })?;
+
+    Ok(ResolvedConfig { config, value, provenance })
+}
+
+/// Read and migrate one config file layer
+fn read_layer(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config {}: {}", path.display(), e))?;
+    let mut value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to load config {}: invalid JSON: {}", path.display(), e))?;
+    migrate::migrate(&mut value).map_err(|e| format!("Failed to load config {}: {}", path.display(), e))?;
+    Ok(value)
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn is_under(key: &str, prefix: &str) -> bool {
+    prefix.is_empty() || key == prefix || key.starts_with(&format!("{}.", prefix))
+}
+
+/// Look up a dotted key in a JSON document
+pub fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, segment| v.get(segment))
+}
+
+fn record_leaves(value: &Value, path: &str, source: &ValueSource, out: &mut BTreeMap<String, ValueSource>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                record_leaves(v, &join_key(path, k), source, out);
+            }
+        }
+        _ => {
+            out.insert(path.to_string(), source.clone());
+        }
+    }
+}
+
+/// Remove `null` members, which only mark deletions in a layer; returns whether any were found
+pub fn strip_deletions(value: &mut Value) -> bool {
+    let Value::Object(map) = value else {
+        return false;
+    };
+    let before = map.len();
+    map.retain(|_, v| !v.is_null());
+    let mut found = map.len() != before;
+    for v in map.values_mut() {
+        found |= strip_deletions(v);
+    }
+    found
+}
+
+/// Deep-merge `overlay` into `base` as an RFC 7396 merge patch: objects merge key by key,
+/// `null` removes the key (e.g. a built-in MCP server or profile), anything else replaces
+fn merge(base: &mut Value, overlay: Value, path: &str, source: &ValueSource, prov: &mut BTreeMap<String, ValueSource>) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (k, v) in overlay {
+                let key = join_key(path, &k);
+                if v.is_null() {
+                    base.remove(&k);
+                    prov.retain(|p, _| !is_under(p, &key));
+                    continue;
+                }
+                match base.get_mut(&k) {
+                    Some(existing) => merge(existing, v, &key, source, prov),
+                    None => {
+                        let mut v = v;
+                        strip_deletions(&mut v);
+                        record_leaves(&v, &key, source, prov);
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, mut overlay) => {
+            strip_deletions(&mut overlay);
+            prov.retain(|k, _| !is_under(k, path));
+            record_leaves(&overlay, path, source, prov);
+            *base = overlay;
+        }
+    }
+}
+
+/// Map `HYDRA_SECTION__KEY=value` onto an overlay document.
+/// Segments match existing keys case-insensitively, with `_` standing in for `-`.
+fn env_override(base: &Value, name: &str, raw: &str) -> Option<(String, Value)> {
+    let rest = name.strip_prefix(ENV_PREFIX)?;
+    let segments: Vec<&str> = rest.split(ENV_SEPARATOR).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+
+    // Only known top-level sections are overridable (skips HYDRA_PATH and friends)
+    let mut keys = Vec::new();
+    let mut current = Some(base);
+    for (i, segment) in segments.iter().enumerate() {
+        let existing = current
+            .and_then(Value::as_object)
+            .and_then(|map| map.keys().find(|k| k.replace('-', "_").eq_ignore_ascii_case(segment)).cloned());
+        let key = match existing {
+            Some(key) => key,
+            None if i == 0 => return None,
+            // `env` keys are conventionally upper case
+            None if keys.first().map(String::as_str) == Some("env") => segment.to_string(),
+            None => segment.to_lowercase(),
+        };
+        current = current.and_then(|v| v.get(&key));
+        keys.push(key);
+    }
+
+    let parsed = match current {
+        // Keep string-typed keys as strings even when they look like numbers
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    };
+    // `HYDRA_ENV=prod` and the like name a whole section; replacing it with a scalar would
+    // break the typed config
+    let replaces_section = match (current, &parsed) {
+        (Some(Value::Object(_)), Value::Object(_)) | (Some(Value::Array(_)), Value::Array(_)) => false,
+        (Some(Value::Object(_) | Value::Array(_)), _) => true,
+        _ => false,
+    };
+    if replaces_section {
+        crate::logger::log_warn(&format!("Ignoring {}: `{}` is a section, not a single value", name, keys.join(".")));
+        return None;
+    }
+
+    let overlay = keys.iter().rev().fold(parsed, |acc, key| {
+        let mut map = Map::new();
+        map.insert(key.clone(), acc);
+        Value::Object(map)
+    });
+    Some((keys.join("."), overlay))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_merge_with_provenance() {
+        let dir = std::env::temp_dir().join(format!("hydra-layers-{}", std::process::id()));
+        fs::create_dir_all(dir.join(".hydra")).unwrap();
+        let user = dir.join("user.json");
+        fs::write(&user, r#"{"logging": {"level": "debug", "max_size_mb": 20}, "mcp_tools": {"playwright": null}}"#).unwrap();
+        fs::write(dir.join(".hydra").join("config"), r#"{"logging": {"level": "warn"}}"#).unwrap();
+        let project = find_project_config(&dir.join("nested")).unwrap();
+
+        let vars = vec![
+            ("HYDRA_LOGGING__ROTATE_COUNT".to_string(), "9".to_string()),
+            ("HYDRA_MCP_TOOLS__DESKTOP_COMMANDER__ENABLED".to_string(), "false".to_string()),
+            ("HYDRA_PATH".to_string(), "/ignored".to_string()),
+            ("HYDRA_ENV".to_string(), "prod".to_string()),
+        ];
+        let resolved = resolve_from(Some(user), Some(project), vars.into_iter()).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(resolved.config.logging.level, "warn");
+        assert_eq!(resolved.config.logging.max_size_mb, 20);
+        assert_eq!(resolved.config.logging.rotate_count, 9);
+        assert!(!resolved.config.mcp_tools["desktop-commander"].enabled);
+        // null deletes a built-in entry instead of merging into it
+        assert!(!resolved.config.mcp_tools.contains_key("playwright"));
+        assert!(!resolved.provenance.keys().any(|k| k.starts_with("mcp_tools.playwright.")));
+        // A scalar never replaces a whole section
+        assert!(!resolved.provenance.values().any(|s| s.origin == "HYDRA_ENV"));
+
+        let layer = |key: &str| resolved.provenance[key].layer;
+        assert_eq!(layer("logging.level"), ConfigLayer::Project);
+        assert_eq!(layer("logging.max_size_mb"), ConfigLayer::User);
+        assert_eq!(layer("logging.rotate_count"), ConfigLayer::Env);
+        assert_eq!(layer("logging.enabled"), ConfigLayer::Default);
+        assert_eq!(resolved.provenance["mcp_tools.desktop-commander.enabled"].origin, "HYDRA_MCP_TOOLS__DESKTOP_COMMANDER__ENABLED");
+    }
+}
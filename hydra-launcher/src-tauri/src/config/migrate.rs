@@ -0,0 +1,204 @@
+use serde_json::{json, Map, Value};
+
+/// Schema version written by this launcher
+pub const CURRENT_VERSION: &str = "10.5.0";
+
+/// Schema key: (major, minor)
+type SchemaVersion = (u32, u32);
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Ordered migration steps: (from schema, to schema, step)
+const MIGRATIONS: &[(SchemaVersion, SchemaVersion, Migration)] = &[((10, 4), (10, 5), migrate_10_4_to_10_5)];
+
+/// Parse "major.minor[.patch]" into a schema key
+fn schema_version(version: &str) -> Option<SchemaVersion> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    Some((major, minor))
+}
+
+/// Detect the schema of a raw config document
+fn detect_version(root: &Map<String, Value>) -> Result<SchemaVersion, String> {
+    match root.get("version") {
+        Some(Value::String(v)) => {
+            schema_version(v).ok_or_else(|| format!("invalid value at `version`: unrecognised version \"{}\"", v))
+        }
+        Some(other) => Err(format!("invalid value at `version`: expected a string, found {}", other)),
+        // Unversioned launcher files only ever had the 10.4 shape
+        None if root.contains_key("mcp_servers") || root.contains_key("yolo_enabled") => Ok((10, 4)),
+        None => schema_version(CURRENT_VERSION).ok_or_else(|| "invalid CURRENT_VERSION".to_string()),
+    }
+}
+
+/// Migrate a raw config document to the current schema in place.
+/// Returns a description of each step applied.
+pub fn migrate(value: &mut Value) -> Result<Vec<String>, String> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| "invalid config: top level must be a JSON object".to_string())?;
+
+    let current = schema_version(CURRENT_VERSION).ok_or_else(|| "invalid CURRENT_VERSION".to_string())?;
+    let mut version = detect_version(root)?;
+    if version > current {
+        return Err(format!(
+            "config schema {}.{} is newer than supported {}.{}",
+            version.0, version.1, current.0, current.1
+        ));
+    }
+
+    let mut applied = Vec::new();
+    while version < current {
+        let (_, to, step) = MIGRATIONS
+            .iter()
+            .find(|(from, _, _)| *from == version)
+            .ok_or_else(|| format!("no migration from config schema {}.{}", version.0, version.1))?;
+
+        step(root).map_err(|e| format!("migration {}.{} -> {}.{} failed: {}", version.0, version.1, to.0, to.1, e))?;
+        applied.push(format!("{}.{} -> {}.{}", version.0, version.1, to.0, to.1));
+        version = *to;
+    }
+
+    if !applied.is_empty() {
+        root.insert("version".to_string(), Value::String(CURRENT_VERSION.to_string()));
+    }
+
+    Ok(applied)
+}
+
+/// 10.4 launcher files: `mode`, `yolo_enabled`, `mcp_servers` array and `ai_handler`
+fn migrate_10_4_to_10_5(root: &mut Map<String, Value>) -> Result<(), String> {
+    let mut env = Map::new();
+    let mut launcher = Map::new();
+
+    if let Some(mode) = root.remove("mode") {
+        let mode = mode.as_str().ok_or("`mode` must be a string")?;
+        env.insert("HYDRA_MODE".to_string(), json!(mode.to_uppercase().replace(' ', "_")));
+    }
+
+    if let Some(yolo) = root.remove("yolo_enabled") {
+        launcher.insert("yolo_enabled".to_string(), yolo);
+    }
+
+    if let Some(ai_handler) = root.remove("ai_handler") {
+        let ai_handler = ai_handler.as_object().ok_or("`ai_handler` must be an object")?;
+        if let Some(prefer_local) = ai_handler.get("prefer_local").and_then(Value::as_bool) {
+            env.insert("PREFER_LOCAL_EXECUTION".to_string(), json!(prefer_local.to_string()));
+        }
+        if let Some(port) = ai_handler.get("ollama_port").and_then(Value::as_u64) {
+            env.insert("OLLAMA_HOST".to_string(), json!(format!("http://localhost:{}", port)));
+        }
+        if let Some(model) = ai_handler.get("default_model").and_then(Value::as_str) {
+            launcher.insert("default_model".to_string(), json!(model));
+            root.insert(
+                "fallback_chain".to_string(),
+                json!({ "local": [{ "provider": "ollama", "model": model, "priority": 1 }] }),
+            );
+        }
+    }
+
+    if let Some(servers) = root.remove("mcp_servers") {
+        let servers = servers.as_array().ok_or("`mcp_servers` must be an array")?;
+        let mut mcp_tools = Map::new();
+        for (i, server) in servers.iter().enumerate() {
+            let server = server
+                .as_object()
+                .ok_or_else(|| format!("`mcp_servers[{}]` must be an object", i))?;
+            // Entries copied from mcp-servers.json use the PowerShell casing
+            let field = |key: &str| server.get(key).or_else(|| server.get(&capitalize(key)));
+            let name = field("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("`mcp_servers[{}].name` is missing", i))?;
+            let command = field("command")
+                .or_else(|| server.get("CommandName"))
+                .cloned()
+                .unwrap_or_else(|| json!(""));
+
+            let mut entry = Map::new();
+            entry.insert("type".to_string(), command.clone());
+            entry.insert("command".to_string(), command);
+            entry.insert("args".to_string(), field("args").cloned().unwrap_or_else(|| json!([])));
+            if let Some(port) = field("port") {
+                entry.insert("health_port".to_string(), port.clone());
+            }
+            if let Some(url) = server.get("HealthUrl") {
+                entry.insert("health_url".to_string(), url.clone());
+            }
+            if let Some(timeout) = server.get("TimeoutSeconds") {
+                entry.insert("timeout_seconds".to_string(), timeout.clone());
+            }
+            match (field("type").and_then(Value::as_str), server.get("ProcessName")) {
+                (Some("Stdio"), Some(process)) => {
+                    entry.insert("health_check".to_string(), json!({ "type": "process", "name": process }));
+                }
+                (Some("Port"), _) => {
+                    entry.insert("health_check".to_string(), json!({ "type": "port" }));
+                }
+                _ => {}
+            }
+            if let Some(enabled) = field("enabled") {
+                entry.insert("enabled".to_string(), enabled.clone());
+            }
+
+            mcp_tools.insert(name.to_lowercase().replace(' ', "-"), Value::Object(entry));
+        }
+        root.insert("mcp_tools".to_string(), Value::Object(mcp_tools));
+    }
+
+    if !env.is_empty() {
+        root.insert("env".to_string(), Value::Object(env));
+    }
+    if !launcher.is_empty() {
+        root.insert("launcher".to_string(), Value::Object(launcher));
+    }
+    root.entry("name".to_string()).or_insert_with(|| json!("HYDRA-Orchestrator"));
+
+    Ok(())
+}
+
+fn capitalize(key: &str) -> String {
+    let mut chars = key.chars();
+    chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{HealthCheckConfig, HydraConfig};
+
+    const LAUNCHER_10_4: &str = r#"{
+        "version": "10.4.0",
+        "mode": "MCP Orchestration",
+        "yolo_enabled": false,
+        "mcp_servers": [
+            {"name": "Desktop Commander", "port": 8100, "command": "npx", "args": ["-y", "dc"], "enabled": false},
+            {"Name": "Playwright", "Port": 5200, "Type": "Stdio", "ProcessName": "playwright", "CommandName": "npx", "TimeoutSeconds": 8}
+        ],
+        "ai_handler": {"prefer_local": true, "ollama_port": 11434, "default_model": "llama3.2:3b"}
+    }"#;
+
+    #[test]
+    fn test_migrate_10_4_launcher_config() {
+        let config = HydraConfig::parse(LAUNCHER_10_4).unwrap();
+        assert_eq!(config.version, CURRENT_VERSION);
+        assert_eq!(config.env["HYDRA_MODE"], "MCP_ORCHESTRATION");
+        assert_eq!(config.env["OLLAMA_HOST"], "http://localhost:11434");
+        assert!(!config.launcher.yolo_enabled);
+        assert_eq!(config.launcher.default_model.as_deref(), Some("llama3.2:3b"));
+
+        let dc = &config.mcp_tools["desktop-commander"];
+        assert_eq!(dc.health_port, Some(8100));
+        assert!(!dc.enabled);
+
+        let playwright = &config.mcp_tools["playwright"];
+        assert_eq!(playwright.timeout_seconds, 8);
+        assert_eq!(playwright.health_check, Some(HealthCheckConfig::Process { name: "playwright".to_string() }));
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let mut value = json!({"version": "11.0.0"});
+        assert!(migrate(&mut value).unwrap_err().contains("newer"));
+    }
+}
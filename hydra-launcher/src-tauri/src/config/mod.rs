@@ -0,0 +1,785 @@
+pub mod bundle;
+pub mod edit;
+pub mod interpolate;
+pub mod layers;
+pub mod migrate;
+pub mod profiles;
+pub mod schema;
+pub mod watcher;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use profiles::ProfileConfig;
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://127.0.0.1:11434";
+
+/// HYDRA configuration (hydra-config.json, schema 10.5)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HydraConfig {
+    /// Editor hint pointing at hydra-config.schema.json
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub env: BTreeMap<String, String>,
+    pub commands: BTreeMap<String, CommandConfig>,
+    pub advanced_ai: AdvancedAiConfig,
+    pub parallel: ParallelConfig,
+    pub fallback_chain: FallbackChainConfig,
+    pub mcp_tools: BTreeMap<String, McpServerConfig>,
+    pub hooks: HooksConfig,
+    pub statusline: StatuslineConfig,
+    pub skills: BTreeMap<String, SkillConfig>,
+    pub system_instructions: SystemInstructionsConfig,
+    pub memory: MemoryConfig,
+    pub permissions: PermissionsConfig,
+    pub logging: LoggingConfig,
+    pub launcher: LauncherConfig,
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// What to do when a service stays down, keyed by MCP server name or `ollama`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub remediation: BTreeMap<String, RemediationRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CommandConfig {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AdvancedAiConfig {
+    pub enabled: bool,
+    pub modules: AdvancedAiModules,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AdvancedAiModules {
+    pub self_correction: SelfCorrectionConfig,
+    pub few_shot_learning: FewShotLearningConfig,
+    pub speculative_decoding: SpeculativeDecodingConfig,
+    pub load_balancer: LoadBalancerConfig,
+    pub semantic_file_mapping: SemanticFileMappingConfig,
+    pub prompt_optimizer: PromptOptimizerConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SelfCorrectionConfig {
+    pub enabled: bool,
+    pub validator_model: String,
+    pub max_attempts: u32,
+    pub supported_languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FewShotLearningConfig {
+    pub enabled: bool,
+    pub cache_path: String,
+    pub max_examples: u32,
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SpeculativeDecodingConfig {
+    pub enabled: bool,
+    pub fast_model: String,
+    pub accurate_model: String,
+    pub code_model: String,
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LoadBalancerConfig {
+    pub enabled: bool,
+    pub cpu_threshold_local: u8,
+    pub cpu_threshold_cloud: u8,
+    pub memory_threshold: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SemanticFileMappingConfig {
+    pub enabled: bool,
+    pub max_depth: u32,
+    pub max_related_files: u32,
+    pub supported_languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PromptOptimizerConfig {
+    pub enabled: bool,
+    pub auto_enhance: bool,
+    pub add_examples: bool,
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ParallelConfig {
+    pub enabled: bool,
+    pub max_concurrent_jobs: u32,
+    pub throttle_ms: u64,
+    pub cpu_target_percent: u8,
+    pub read_only_parallel: bool,
+    pub batch_size: u32,
+    pub read_only_tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FallbackChainConfig {
+    pub local: Vec<FallbackEntry>,
+    pub cloud: Vec<FallbackEntry>,
+    pub priority: String,
+    pub auto_fallback: bool,
+    pub max_retries: u32,
+    pub retry_delay_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FallbackEntry {
+    pub provider: String,
+    pub model: String,
+    pub priority: u32,
+}
+
+/// MCP server entry from the `mcp_tools` section, keyed by server name
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpServerConfig {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub health_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_url: Option<String>,
+    /// How the launcher talks MCP to the server; inferred from `health_url` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<McpTransport>,
+    /// Health check strategy; an MCP handshake when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check: Option<HealthCheckConfig>,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Seconds between background health checks
+    #[serde(default = "default_health_interval_seconds")]
+    #[schemars(range(min = 1))]
+    pub health_interval_seconds: u64,
+    /// Response time that counts as slow in `mcp-status-changed`; 80% of `timeout_seconds` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_threshold_ms: Option<u64>,
+    #[serde(default)]
+    pub key_tools: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub restart: RestartConfig,
+    /// How long a stopping server gets after SIGTERM before its process group is killed
+    #[serde(default = "default_shutdown_grace_ms")]
+    pub shutdown_grace_ms: u64,
+    /// Servers (or `ollama`) that must be ready before this one starts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// When dependents may start; as soon as the process runs when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_when: Option<ReadyCondition>,
+    /// How long dependents wait for `ready_when`
+    #[serde(default = "default_ready_timeout_seconds")]
+    pub ready_timeout_seconds: u64,
+}
+
+/// When the supervisor restarts a server that exited
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    Never,
+    /// Only after a non-zero exit or a crash
+    #[default]
+    OnFailure,
+    Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RestartConfig {
+    pub policy: RestartPolicy,
+    /// First restart delay; doubles with every restart inside the window
+    pub backoff_initial_ms: u64,
+    pub backoff_max_ms: u64,
+    /// Give up after this many restarts within `window_seconds`
+    pub max_restarts: u32,
+    pub window_seconds: u64,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::OnFailure,
+            backoff_initial_ms: 1000,
+            backoff_max_ms: 30_000,
+            max_restarts: 5,
+            window_seconds: 300,
+        }
+    }
+}
+
+/// Action taken when a monitored service is down
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemediationAction {
+    /// Restart the MCP server under the supervisor with its configured command
+    Restart,
+    /// Run `ollama serve`
+    StartOllama,
+    /// Only log and emit `remediation`
+    Notify,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct RemediationRule {
+    pub action: RemediationAction,
+    /// Consecutive failed checks before acting
+    #[serde(default = "default_after_failures")]
+    #[schemars(range(min = 1))]
+    pub after_failures: u32,
+    /// Minimum time between two actions
+    #[serde(default = "default_cooldown_seconds")]
+    pub cooldown_seconds: u64,
+    /// Actions without a recovery before giving up until the service is back
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+/// MCP transport
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// JSON-RPC over the child's stdin/stdout
+    Stdio,
+    /// Legacy HTTP+SSE: GET an event stream, POST to the announced endpoint
+    Sse,
+    /// Streamable HTTP: POST to a single endpoint
+    Http,
+}
+
+/// How a server's health is judged
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HealthCheckConfig {
+    /// TCP connect to `health_port`
+    Port,
+    /// GET `health_url`; a wrong status or missing body text reports degraded
+    Http {
+        #[serde(default = "default_expect_status")]
+        expect_status: u16,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expect_body: Option<String>,
+    },
+    /// A running process whose name or command line contains `name`
+    Process { name: String },
+    /// MCP `initialize` + `ping` over the server's transport
+    Mcp,
+}
+
+/// Readiness gate a server must pass before its dependents are started
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReadyCondition {
+    /// `health_port` accepts connections
+    Port,
+    /// GET `health_url` answers with `expect_status`
+    Http {
+        #[serde(default = "default_expect_status")]
+        expect_status: u16,
+    },
+    /// MCP `initialize` + `ping` succeed; stdio servers count as ready once running
+    Mcp,
+}
+
+impl McpServerConfig {
+    /// Configured transport, else `sse` for `/sse` URLs, `http` for other URLs and `stdio` without one
+    pub fn transport(&self) -> McpTransport {
+        match (self.transport, self.health_url.as_deref()) {
+            (Some(transport), _) => transport,
+            (None, Some(url)) if url.trim_end_matches('/').ends_with("/sse") => McpTransport::Sse,
+            (None, Some(_)) => McpTransport::Http,
+            (None, None) => McpTransport::Stdio,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HooksConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_prompt_submit: Option<HookConfig>,
+    pub notification: BTreeMap<String, HookConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HookConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub matcher: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct StatuslineConfig {
+    pub enabled: bool,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub script: String,
+    pub refresh_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SkillConfig {
+    pub path: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub triggers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SystemInstructionsConfig {
+    pub role: String,
+    pub doctrine: String,
+    pub autonomous_mode: AutonomousModeConfig,
+    pub council_of_six: BTreeMap<String, String>,
+    pub cost_priority: Vec<CostTier>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AutonomousModeConfig {
+    pub enabled: bool,
+    pub auto_execute: bool,
+    pub no_confirmations: bool,
+    pub visual_formatting: bool,
+    pub suggest_next_prompts: bool,
+    pub ascii_diagrams: bool,
+    pub markdown_tables: bool,
+    pub code_blocks_with_syntax: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CostTier {
+    pub tier: u32,
+    pub provider: String,
+    pub cost: String,
+    pub use_for: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MemoryConfig {
+    pub provider: String,
+    pub max_slots: u32,
+    pub path: String,
+    pub priority_memories: Vec<String>,
+    pub auto_save: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PermissionsConfig {
+    pub mode: String,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub dangerous_operations_require_confirmation: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LoggingConfig {
+    pub enabled: bool,
+    pub level: String,
+    pub path: String,
+    pub max_size_mb: u64,
+    pub rotate_count: u32,
+}
+
+/// Launcher-only settings (absent from PowerShell-era files)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LauncherConfig {
+    pub yolo_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+    /// Name of the entry in `profiles` overlaid on this config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Start every enabled MCP server under the supervisor when the launcher opens
+    pub autostart_mcp: bool,
+    /// Days of health probes kept for `get_health_history`
+    pub health_history_days: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_expect_status() -> u16 {
+    200
+}
+
+fn default_timeout_seconds() -> u64 {
+    5
+}
+
+fn default_after_failures() -> u32 {
+    2
+}
+
+fn default_cooldown_seconds() -> u64 {
+    60
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_health_interval_seconds() -> u64 {
+    30
+}
+
+fn default_shutdown_grace_ms() -> u64 {
+    5000
+}
+
+fn default_ready_timeout_seconds() -> u64 {
+    30
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for HydraConfig {
+    fn default() -> Self {
+        let mcp_server = |kind: &str, command: &str, args: &[&str], port: u16| McpServerConfig {
+            kind: kind.to_string(),
+            command: command.to_string(),
+            args: strings(args),
+            health_port: Some(port),
+            health_url: None,
+            transport: None,
+            health_check: None,
+            timeout_seconds: default_timeout_seconds(),
+            health_interval_seconds: default_health_interval_seconds(),
+            latency_threshold_ms: None,
+            key_tools: Vec::new(),
+            enabled: true,
+            restart: RestartConfig::default(),
+            shutdown_grace_ms: default_shutdown_grace_ms(),
+            depends_on: Vec::new(),
+            ready_when: None,
+            ready_timeout_seconds: default_ready_timeout_seconds(),
+        };
+
+        let mut mcp_tools = BTreeMap::new();
+        mcp_tools.insert(
+            "serena".to_string(),
+            mcp_server("uvx", "uvx", &["--from", "git+https://github.com/oraios/serena", "serena", "start-mcp-server"], 9000),
+        );
+        mcp_tools.insert(
+            "desktop-commander".to_string(),
+            mcp_server("npx", "npx", &["-y", "@wonderwhy-er/desktop-commander"], 8100),
+        );
+        mcp_tools.insert(
+            "playwright".to_string(),
+            mcp_server("npx", "npx", &["-y", "@playwright/mcp@latest"], 5200),
+        );
+
+        Self {
+            schema: None,
+            name: "HYDRA-Orchestrator".to_string(),
+            version: migrate::CURRENT_VERSION.to_string(),
+            description: String::new(),
+            env: BTreeMap::new(),
+            commands: BTreeMap::new(),
+            advanced_ai: AdvancedAiConfig::default(),
+            parallel: ParallelConfig::default(),
+            fallback_chain: FallbackChainConfig::default(),
+            mcp_tools,
+            hooks: HooksConfig::default(),
+            statusline: StatuslineConfig::default(),
+            skills: BTreeMap::new(),
+            system_instructions: SystemInstructionsConfig::default(),
+            memory: MemoryConfig::default(),
+            permissions: PermissionsConfig::default(),
+            logging: LoggingConfig::default(),
+            launcher: LauncherConfig::default(),
+            profiles: profiles::builtin_profiles(),
+            remediation: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for AdvancedAiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            modules: AdvancedAiModules::default(),
+        }
+    }
+}
+
+impl Default for SelfCorrectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            validator_model: "phi3:mini".to_string(),
+            max_attempts: 3,
+            supported_languages: Vec::new(),
+        }
+    }
+}
+
+impl Default for FewShotLearningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cache_path: "ai-handler/cache".to_string(),
+            max_examples: 5,
+            categories: Vec::new(),
+        }
+    }
+}
+
+impl Default for SpeculativeDecodingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fast_model: "llama3.2:1b".to_string(),
+            accurate_model: "llama3.2:3b".to_string(),
+            code_model: "qwen2.5-coder:1.5b".to_string(),
+            timeout_ms: 30000,
+        }
+    }
+}
+
+impl Default for LoadBalancerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cpu_threshold_local: 70,
+            cpu_threshold_cloud: 90,
+            memory_threshold: 85,
+        }
+    }
+}
+
+impl Default for SemanticFileMappingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_depth: 3,
+            max_related_files: 5,
+            supported_languages: Vec::new(),
+        }
+    }
+}
+
+impl Default for PromptOptimizerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_enhance: true,
+            add_examples: false,
+            categories: Vec::new(),
+        }
+    }
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_concurrent_jobs: 8,
+            throttle_ms: 100,
+            cpu_target_percent: 80,
+            read_only_parallel: true,
+            batch_size: 4,
+            read_only_tools: Vec::new(),
+        }
+    }
+}
+
+impl Default for FallbackChainConfig {
+    fn default() -> Self {
+        Self {
+            local: vec![FallbackEntry {
+                provider: "ollama".to_string(),
+                model: "llama3.2:3b".to_string(),
+                priority: 1,
+            }],
+            cloud: Vec::new(),
+            priority: "local_first".to_string(),
+            auto_fallback: true,
+            max_retries: 3,
+            retry_delay_ms: 1000,
+        }
+    }
+}
+
+impl Default for StatuslineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            kind: "command".to_string(),
+            script: String::new(),
+            refresh_ms: 5000,
+        }
+    }
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            provider: "serena".to_string(),
+            max_slots: 25,
+            path: ".serena/memories/".to_string(),
+            priority_memories: Vec::new(),
+            auto_save: true,
+        }
+    }
+}
+
+impl Default for PermissionsConfig {
+    fn default() -> Self {
+        Self {
+            mode: "default".to_string(),
+            allow: Vec::new(),
+            deny: Vec::new(),
+            dangerous_operations_require_confirmation: strings(&["rm -rf /", "format", "diskpart"]),
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            level: "info".to_string(),
+            path: "logs/".to_string(),
+            max_size_mb: 10,
+            rotate_count: 5,
+        }
+    }
+}
+
+impl Default for LauncherConfig {
+    fn default() -> Self {
+        Self {
+            yolo_enabled: true,
+            default_model: None,
+            active_profile: None,
+            autostart_mcp: false,
+            health_history_days: 7,
+        }
+    }
+}
+
+impl HydraConfig {
+    /// Parse a hydra-config.json document, migrating older schema versions forward
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut value: Value = serde_json::from_str(content)
+            .map_err(|e| format!("invalid JSON: {}", e))?;
+
+        let applied = migrate::migrate(&mut value)?;
+        // `null` deletes a key from lower layers and has no meaning in a single document
+        let deletions = layers::strip_deletions(&mut value);
+        if applied.is_empty() && !deletions {
+            // Type-check the source text first so errors keep their line/column
+            let mut de = serde_json::Deserializer::from_str(content);
+            serde_path_to_error::deserialize::<_, Self>(&mut de).map_err(describe_error)?;
+        }
+        for step in &applied {
+            crate::logger::log_info(&format!("Config migrated: {}", step));
+        }
+
+        interpolate::interpolate(&mut value, |name| std::env::var(name).ok())?;
+        serde_path_to_error::deserialize(value).map_err(describe_error)
+    }
+
+    /// Ollama base URL from `env.OLLAMA_HOST`
+    pub fn ollama_url(&self) -> String {
+        match self.env.get("OLLAMA_HOST").map(|h| h.trim().trim_end_matches('/')) {
+            Some(host) if host.contains("://") => host.to_string(),
+            Some(host) if !host.is_empty() => format!("http://{}", host),
+            _ => DEFAULT_OLLAMA_URL.to_string(),
+        }
+    }
+
+    /// `env` block with secret references resolved, for child processes only
+    pub fn process_env(&self) -> Result<BTreeMap<String, String>, String> {
+        crate::secrets::resolve_env(&self.env)
+    }
+}
+
+/// Format a deserialization error with the key path it occurred at
+fn describe_error(err: serde_path_to_error::Error<serde_json::Error>) -> String {
+    let path = err.path().to_string();
+    let inner = err.into_inner();
+    if path == "." {
+        inner.to_string()
+    } else {
+        format!("invalid value at `{}`: {}", path, inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED_CONFIG: &str = include_str!("../../../../hydra-config.json");
+
+    #[test]
+    fn test_parse_shipped_config() {
+        let config = HydraConfig::parse(SHIPPED_CONFIG).unwrap();
+        assert_eq!(config.version, "10.5.0");
+        assert_eq!(config.mcp_tools["serena"].health_port, Some(9000));
+        assert_eq!(config.fallback_chain.local.len(), 3);
+        assert_eq!(config.permissions.mode, "maximum_autonomy");
+        assert_eq!(config.logging.rotate_count, 5);
+        assert!(config.commands["ai"].command.contains(r"BIURODOM\Desktop\ClaudeHYDRA\ai-handler\Invoke-AI.ps1"));
+        assert!(config.mcp_tools["serena"].args.iter().all(|arg| !arg.contains("${")));
+    }
+
+    #[test]
+    fn test_parse_error_reports_path() {
+        let err = HydraConfig::parse(r#"{"version": "10.5.0", "logging": {"max_size_mb": "big"}}"#)
+            .unwrap_err();
+        assert!(err.contains("`logging.max_size_mb`"), "{}", err);
+        assert!(err.contains("line 1"), "{}", err);
+    }
+}
@@ -0,0 +1,153 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Named overlay on top of the base config
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProfileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MCP server name -> enabled
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mcp_servers: BTreeMap<String, bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yolo_enabled: Option<bool>,
+    /// Replaces `fallback_chain.priority`, e.g. "local_only"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PermissionsOverlay>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PermissionsOverlay {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub active: bool,
+}
+
+/// Profiles available without any config file
+pub fn builtin_profiles() -> BTreeMap<String, ProfileConfig> {
+    let mut profiles = BTreeMap::new();
+    profiles.insert(
+        "offline".to_string(),
+        ProfileConfig {
+            description: Some("Local Ollama models only, no browser automation".to_string()),
+            mcp_servers: BTreeMap::from([("playwright".to_string(), false)]),
+            fallback_priority: Some("local_only".to_string()),
+            ..Default::default()
+        },
+    );
+    profiles.insert(
+        "safe".to_string(),
+        ProfileConfig {
+            description: Some("Locked down for client repositories".to_string()),
+            yolo_enabled: Some(false),
+            permissions: Some(PermissionsOverlay {
+                mode: Some("default".to_string()),
+                allow: Some(["Read", "Glob", "Grep"].iter().map(|s| s.to_string()).collect()),
+                deny: Some(vec!["Bash".to_string()]),
+            }),
+            ..Default::default()
+        },
+    );
+    profiles.insert(
+        "full-autonomy".to_string(),
+        ProfileConfig {
+            description: Some("Maximum autonomy YOLO setup".to_string()),
+            yolo_enabled: Some(true),
+            permissions: Some(PermissionsOverlay {
+                mode: Some("maximum_autonomy".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    );
+    profiles
+}
+
+/// Profile names may be used as config keys and in env overrides
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(())
+    } else {
+        Err(format!("Invalid profile name `{}`: use letters, digits, '-' or '_'", name))
+    }
+}
+
+/// Build a config overlay for the active profile of a merged document.
+/// Returns `None` when no profile is active.
+pub fn active_overlay(document: &Value) -> Result<Option<(String, Value)>, String> {
+    let Some(name) = document.pointer("/launcher/active_profile").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+
+    let raw = document
+        .pointer(&format!("/profiles/{}", name))
+        .cloned()
+        .ok_or_else(|| format!("invalid value at `launcher.active_profile`: profile `{}` is not defined", name))?;
+    let profile: ProfileConfig = serde_path_to_error::deserialize(raw)
+        .map_err(|e| format!("invalid value at `profiles.{}.{}`: {}", name, e.path(), e.inner()))?;
+
+    let mut overlay = Map::new();
+    if !profile.mcp_servers.is_empty() {
+        // Servers the base config doesn't define are skipped rather than half-created
+        let defined = document.get("mcp_tools").and_then(Value::as_object);
+        let servers: Map<String, Value> = profile
+            .mcp_servers
+            .iter()
+            .filter(|(server, _)| defined.is_some_and(|d| d.contains_key(*server)))
+            .map(|(server, enabled)| (server.clone(), json!({ "enabled": enabled })))
+            .collect();
+        overlay.insert("mcp_tools".to_string(), Value::Object(servers));
+    }
+    if let Some(yolo) = profile.yolo_enabled {
+        overlay.insert("launcher".to_string(), json!({ "yolo_enabled": yolo }));
+    }
+    if let Some(priority) = profile.fallback_priority {
+        overlay.insert("fallback_chain".to_string(), json!({ "priority": priority }));
+    }
+    if let Some(permissions) = profile.permissions {
+        let permissions = serde_json::to_value(permissions).map_err(|e| e.to_string())?;
+        overlay.insert("permissions".to_string(), permissions);
+    }
+
+    Ok(Some((name.to_string(), Value::Object(overlay))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::layers::{resolve_from, ConfigLayer};
+    use std::fs;
+
+    #[test]
+    fn test_active_profile_overlays_base() {
+        let dir = std::env::temp_dir().join(format!("hydra-profiles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let user = dir.join("hydra-config.json");
+        fs::write(&user, r#"{"launcher": {"active_profile": "offline"}, "fallback_chain": {"priority": "cloud_first"}}"#).unwrap();
+
+        let resolved = resolve_from(Some(user.clone()), None, std::iter::empty()).unwrap();
+        assert_eq!(resolved.config.fallback_chain.priority, "local_only");
+        assert!(!resolved.config.mcp_tools["playwright"].enabled);
+        assert_eq!(resolved.provenance["fallback_chain.priority"].layer, ConfigLayer::Profile);
+
+        fs::write(&user, r#"{"launcher": {"active_profile": "missing"}}"#).unwrap();
+        let err = resolve_from(Some(user), None, std::iter::empty()).unwrap_err();
+        fs::remove_dir_all(&dir).ok();
+        assert!(err.contains("profile `missing` is not defined"), "{}", err);
+    }
+}
@@ -0,0 +1,433 @@
+use super::layers::{self, ConfigLayer, ResolvedConfig};
+use super::{migrate, HydraConfig, RemediationAction};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A single validation finding, located in the file that caused it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigDiagnostic {
+    pub file: Option<String>,
+    pub path: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// JSON Schema for hydra-config.json, generated from the config types.
+/// Struct objects are closed so editors flag unknown keys.
+pub fn generate() -> Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(HydraConfig)).unwrap_or_default();
+    close_objects(&mut schema);
+    schema
+}
+
+fn close_objects(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            if map.contains_key("properties") && !map.contains_key("additionalProperties") {
+                map.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            map.values_mut().for_each(close_objects);
+        }
+        Value::Array(items) => items.iter_mut().for_each(close_objects),
+        _ => {}
+    }
+}
+
+/// Validate one config file's text against the schema
+pub fn validate_document(content: &str, file: Option<&str>) -> Vec<ConfigDiagnostic> {
+    let diag = |path: &str, pos: Option<(usize, usize)>, message: String, severity| ConfigDiagnostic {
+        file: file.map(str::to_string),
+        path: path.to_string(),
+        line: pos.map(|p| p.0),
+        column: pos.map(|p| p.1),
+        message,
+        severity,
+    };
+
+    let mut value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return vec![diag("", Some((e.line(), e.column())), format!("invalid JSON: {}", e), Severity::Error)],
+    };
+
+    let mut scan = Scanner::new(content);
+    scan.value("");
+
+    let mut out = Vec::new();
+    let mut positions = scan.positions;
+    match migrate::migrate(&mut value) {
+        Ok(applied) if !applied.is_empty() => {
+            // Locations refer to the old layout and no longer match
+            positions.clear();
+            out.push(diag(
+                "version",
+                None,
+                format!("file uses an older schema, migrated on load ({})", applied.join(", ")),
+                Severity::Info,
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => return vec![diag("", None, e, Severity::Error)],
+    }
+    let at = |path: &str| positions.get(path).copied();
+
+    for (path, parent) in &scan.duplicates {
+        let (message, severity) = if parent == "mcp_tools" {
+            (format!("duplicate MCP server name `{}`", last_segment(path)), Severity::Error)
+        } else {
+            (format!("duplicate key `{}`, the last value wins", last_segment(path)), Severity::Warning)
+        };
+        out.push(diag(path, at(path), message, severity));
+    }
+
+    // `null` deletes a key from lower layers, e.g. a built-in MCP server
+    layers::strip_deletions(&mut value);
+
+    let schema = generate();
+    let mut unknown = Vec::new();
+    unknown_keys(&value, &schema, &schema, "", &mut unknown);
+    for path in unknown {
+        out.push(diag(&path, at(&path), format!("unknown key `{}`", last_segment(&path)), Severity::Warning));
+    }
+
+    let mut port_paths = Vec::new();
+    if let Some(servers) = value.get("mcp_tools").and_then(Value::as_object) {
+        let mut seen: HashMap<String, &String> = HashMap::new();
+        for name in servers.keys() {
+            let normalized = name.to_lowercase().replace(['-', '_', ' '], "");
+            if let Some(other) = seen.insert(normalized, name) {
+                let path = format!("mcp_tools.{}", name);
+                out.push(diag(&path, at(&path), format!("duplicate MCP server name `{}` (also `{}`)", name, other), Severity::Error));
+            }
+        }
+
+        for (name, server) in servers {
+            let path = format!("mcp_tools.{}.health_port", name);
+            if let Some(port) = server.get("health_port").and_then(Value::as_i64) {
+                if !(1..=65535).contains(&port) {
+                    out.push(diag(&path, at(&path), format!("port {} out of range (1-65535)", port), Severity::Error));
+                    port_paths.push(path);
+                }
+            }
+        }
+    }
+
+    if let Err(err) = serde_path_to_error::deserialize::<_, HydraConfig>(value) {
+        let path = err.path().to_string();
+        if !port_paths.contains(&path) {
+            out.push(diag(&path, at(&path), err.into_inner().to_string(), Severity::Error));
+        }
+    }
+
+    out
+}
+
+/// Cross-field checks on the effective (merged) config
+pub fn validate_resolved(resolved: &ResolvedConfig) -> Vec<ConfigDiagnostic> {
+    let config = &resolved.config;
+    let mut out = Vec::new();
+    let mut diag = |path: &str, message: String, severity| {
+        let file = resolved
+            .provenance
+            .get(path)
+            .filter(|s| matches!(s.layer, ConfigLayer::User | ConfigLayer::Project))
+            .map(|s| s.origin.clone());
+        out.push(ConfigDiagnostic { file, path: path.to_string(), line: None, column: None, message, severity });
+    };
+
+    if let Some(model) = &config.launcher.default_model {
+        let chain = &config.fallback_chain;
+        if !chain.local.iter().chain(&chain.cloud).any(|entry| &entry.model == model) {
+            diag(
+                "launcher.default_model",
+                format!("default model `{}` is not in fallback_chain.local or fallback_chain.cloud", model),
+                Severity::Warning,
+            );
+        }
+    }
+
+    for issue in crate::mcp::deps::dependency_issues(&config.mcp_tools) {
+        diag(&format!("mcp_tools.{}.depends_on", issue.server), issue.message, Severity::Error);
+    }
+
+    for (target, rule) in &config.remediation {
+        let path = format!("remediation.{}", target);
+        let is_ollama = target == crate::mcp::history::OLLAMA_TARGET;
+        if !is_ollama && !config.mcp_tools.contains_key(target) {
+            diag(&path, format!("`{}` is neither an MCP server nor `ollama`", target), Severity::Warning);
+        } else if is_ollama && rule.action == RemediationAction::Restart {
+            diag(&format!("{}.action", path), "`restart` only applies to MCP servers; use `start-ollama`".to_string(), Severity::Error);
+        }
+    }
+
+    let mut ports: BTreeMap<u16, Vec<&str>> = BTreeMap::new();
+    for (name, server) in config.mcp_tools.iter().filter(|(_, s)| s.enabled) {
+        if let Some(port) = server.health_port {
+            ports.entry(port).or_default().push(name);
+        }
+    }
+    for (port, names) in ports.into_iter().filter(|(_, names)| names.len() > 1) {
+        for name in &names[1..] {
+            diag(
+                &format!("mcp_tools.{}.health_port", name),
+                format!("port {} is also used by `{}`", port, names[0]),
+                Severity::Warning,
+            );
+        }
+    }
+
+    out
+}
+
+/// Validate every config file layer, plus the merged result
+pub fn validate_layers(resolved: &ResolvedConfig) -> Vec<ConfigDiagnostic> {
+    let mut out = Vec::new();
+    for file in layers::config_files() {
+        let name = file.display().to_string();
+        match fs::read_to_string(&file) {
+            Ok(content) => out.extend(validate_document(&content, Some(&name))),
+            Err(e) => out.push(ConfigDiagnostic {
+                file: Some(name),
+                path: String::new(),
+                line: None,
+                column: None,
+                message: format!("Failed to read config: {}", e),
+                severity: Severity::Error,
+            }),
+        }
+    }
+    out.extend(validate_resolved(resolved));
+    out
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit('.').next().unwrap_or(path)
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Follow `$ref` and nullable `anyOf` wrappers to the schema that describes a value
+fn resolve_schema<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix("#/")
+            .map(|p| p.split('/').try_fold(root, |v, segment| v.get(segment)))
+            .unwrap_or(None);
+        return target.map(|t| resolve_schema(t, root)).unwrap_or(schema);
+    }
+    if let Some(options) = schema.get("anyOf").or_else(|| schema.get("oneOf")).and_then(Value::as_array) {
+        if let Some(inner) = options.iter().find(|o| o.get("type") != Some(&Value::String("null".to_string()))) {
+            return resolve_schema(inner, root);
+        }
+    }
+    schema
+}
+
+fn unknown_keys(value: &Value, schema: &Value, root: &Value, path: &str, out: &mut Vec<String>) {
+    let schema = resolve_schema(schema, root);
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties").filter(|a| a.is_object());
+            for (key, child) in map {
+                let child_path = join_key(path, key);
+                match properties.and_then(|p| p.get(key)).or(additional) {
+                    Some(child_schema) => unknown_keys(child, child_schema, root, &child_path, out),
+                    None if properties.is_some() => out.push(child_path),
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    unknown_keys(item, item_schema, root, &format!("{}[{}]", path, i), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Minimal JSON scanner that records where each key path starts in the source
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+    positions: HashMap<String, (usize, usize)>,
+    /// (duplicate key path, parent path)
+    duplicates: Vec<(String, String)>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0, line: 1, column: 1, positions: HashMap::new(), duplicates: Vec::new() }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.bump();
+        loop {
+            match self.bump()? {
+                '\\' => {
+                    self.bump();
+                }
+                '"' => break,
+                _ => {}
+            }
+        }
+        serde_json::from_str(&self.text[start..self.pos]).ok()
+    }
+
+    fn value(&mut self, path: &str) -> Option<()> {
+        self.skip_ws();
+        self.positions.entry(path.to_string()).or_insert((self.line, self.column));
+        match self.peek()? {
+            '{' => {
+                self.bump();
+                let mut seen = Map::new();
+                loop {
+                    self.skip_ws();
+                    match self.peek()? {
+                        '}' => {
+                            self.bump();
+                            return Some(());
+                        }
+                        ',' => {
+                            self.bump();
+                        }
+                        '"' => {
+                            let at = (self.line, self.column);
+                            let key = self.string()?;
+                            let child = join_key(path, &key);
+                            if seen.insert(key, Value::Null).is_some() {
+                                self.duplicates.push((child.clone(), path.to_string()));
+                            }
+                            self.positions.insert(child.clone(), at);
+                            self.skip_ws();
+                            if self.bump()? != ':' {
+                                return None;
+                            }
+                            self.value(&child)?;
+                        }
+                        _ => return None,
+                    }
+                }
+            }
+            '[' => {
+                self.bump();
+                let mut index = 0;
+                loop {
+                    self.skip_ws();
+                    match self.peek()? {
+                        ']' => {
+                            self.bump();
+                            return Some(());
+                        }
+                        ',' => {
+                            self.bump();
+                        }
+                        _ => {
+                            self.value(&format!("{}[{}]", path, index))?;
+                            index += 1;
+                        }
+                    }
+                }
+            }
+            '"' => self.string().map(|_| ()),
+            _ => {
+                while self.peek().is_some_and(|c| !matches!(c, ',' | '}' | ']') && !c.is_whitespace()) {
+                    self.bump();
+                }
+                Some(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_schema_is_current() {
+        let generated = serde_json::to_string_pretty(&generate()).unwrap() + "\n";
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../hydra-config.schema.json");
+        if std::env::var("UPDATE_SCHEMA").is_ok() {
+            fs::write(path, &generated).unwrap();
+        }
+        let shipped = fs::read_to_string(path).unwrap_or_default();
+        assert!(shipped == generated, "hydra-config.schema.json is stale, rerun with UPDATE_SCHEMA=1");
+    }
+
+    #[test]
+    fn test_shipped_config_is_clean() {
+        let diagnostics = validate_document(include_str!("../../../../hydra-config.json"), None);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_diagnostics_are_located() {
+        let content = r#"{
+  "version": "10.5.0",
+  "colour": "green",
+  "mcp_tools": {
+    "serena": {"command": "uvx", "health_port": 0},
+    "Serena": {"command": "uvx", "health_port": 9001}
+  }
+}"#;
+        let diagnostics = validate_document(content, Some("hydra-config.json"));
+        let find = |path: &str| diagnostics.iter().find(|d| d.path == path).unwrap();
+
+        let unknown = find("colour");
+        assert_eq!((unknown.line, unknown.column), (Some(3), Some(3)));
+        assert_eq!(unknown.severity, Severity::Warning);
+
+        let port = find("mcp_tools.serena.health_port");
+        assert_eq!(port.line, Some(5));
+        assert!(port.message.contains("out of range"));
+
+        assert_eq!(find("mcp_tools.serena").severity, Severity::Error);
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+    }
+}
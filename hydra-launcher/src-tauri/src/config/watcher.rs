@@ -0,0 +1,252 @@
+use super::edit::{self, ConfigBackup};
+use super::layers::{self, LayerSources, ResolvedConfig};
+use super::{schema, HydraConfig};
+use crate::logger::{log_error, log_info, log_warn};
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Quiet period before reloading, so editors that write in several steps reload once
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A single key that differs between two config revisions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfigKeyChange {
+    pub key: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Payload of the `config-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChangedEvent {
+    pub changes: Vec<ConfigKeyChange>,
+}
+
+/// Payload of the `config-error` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigErrorEvent {
+    pub error: String,
+}
+
+/// Shared in-memory config; keeps the last good revision when a reload fails
+pub struct ConfigStore {
+    resolved: RwLock<ResolvedConfig>,
+    last_error: RwLock<Option<String>>,
+}
+
+impl ConfigStore {
+    /// Resolve all layers, falling back to defaults if the files are invalid
+    pub fn load() -> Self {
+        let (resolved, error) = match layers::resolve() {
+            Ok(resolved) => (resolved, None),
+            Err(e) => {
+                log_error(&format!("Config invalid, using defaults: {}", e));
+                let fallback = layers::resolve_from(None, None, std::iter::empty())
+                    .expect("built-in defaults must resolve");
+                (fallback, Some(e))
+            }
+        };
+
+        log_diagnostics(&resolved);
+        Self {
+            resolved: RwLock::new(resolved),
+            last_error: RwLock::new(error),
+        }
+    }
+
+    pub fn current(&self) -> HydraConfig {
+        self.resolved.read().unwrap().config.clone()
+    }
+
+    pub fn resolved(&self) -> ResolvedConfig {
+        self.resolved.read().unwrap().clone()
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.read().unwrap().clone()
+    }
+
+    /// Re-resolve every layer. On failure the previous config stays active.
+    pub fn reload(&self) -> Result<Vec<ConfigKeyChange>, String> {
+        let next = match layers::resolve() {
+            Ok(next) => next,
+            Err(e) => {
+                *self.last_error.write().unwrap() = Some(e.clone());
+                return Err(e);
+            }
+        };
+
+        *self.last_error.write().unwrap() = None;
+        log_diagnostics(&next);
+        let mut current = self.resolved.write().unwrap();
+        let changes = diff(&current.value, &next.value);
+        *current = next;
+        Ok(changes)
+    }
+
+    /// Apply a merge patch to the user config file and reload
+    pub fn update(&self, patch: &Value) -> Result<Vec<ConfigKeyChange>, String> {
+        edit::update_config_file(&LayerSources::current(), &layers::user_config_path(), patch)?;
+        self.reload()
+    }
+
+    /// Restore a user config backup and reload
+    pub fn restore_backup(&self, name: &str) -> Result<Vec<ConfigKeyChange>, String> {
+        edit::restore_backup(&LayerSources::current(), &layers::user_config_path(), name)?;
+        self.reload()
+    }
+
+    pub fn backups(&self) -> Vec<ConfigBackup> {
+        edit::list_backups(&layers::user_config_path())
+    }
+
+}
+
+/// Surface schema findings for a freshly loaded config in the HYDRA log
+fn log_diagnostics(resolved: &ResolvedConfig) {
+    for d in schema::validate_layers(resolved) {
+        let location = match (d.line, d.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", d.file.as_deref().unwrap_or("config"), line, column),
+            _ => d.file.clone().unwrap_or_else(|| "config".to_string()),
+        };
+        log_warn(&format!("Config {:?} at {} `{}`: {}", d.severity, location, d.path, d.message));
+    }
+}
+
+/// Flatten a document into dotted leaf keys
+fn flatten(value: &Value, path: &str, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = if path.is_empty() { k.clone() } else { format!("{}.{}", path, k) };
+                flatten(v, &key, out);
+            }
+        }
+        _ => {
+            out.insert(path.to_string(), value.clone());
+        }
+    }
+}
+
+/// Keys added, removed or changed between two config documents
+pub fn diff(old: &Value, new: &Value) -> Vec<ConfigKeyChange> {
+    let (mut before, mut after) = (BTreeMap::new(), BTreeMap::new());
+    flatten(old, "", &mut before);
+    flatten(new, "", &mut after);
+
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .map(|key| ConfigKeyChange {
+            key: key.clone(),
+            old: before.get(key).cloned(),
+            new: after.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// Directories to watch: the user config dir, every existing `.hydra` dir on the project
+/// search path, and the search roots themselves so a `.hydra` created later is noticed
+fn watch_dirs() -> BTreeSet<PathBuf> {
+    let mut dirs: BTreeSet<PathBuf> = layers::user_config_path().parent().map(PathBuf::from).into_iter().collect();
+    for root in layers::project_search_roots() {
+        dirs.extend(root.ancestors().map(|dir| dir.join(".hydra")).filter(|d| d.is_dir()));
+        dirs.insert(root);
+    }
+    dirs
+}
+
+/// Whether an event path can change a config layer: the user file, a `.hydra` dir or anything in one
+fn affects_config(path: &Path, user_config: &Path) -> bool {
+    let is_hydra = |p: Option<&Path>| p.and_then(Path::file_name).is_some_and(|n| n == ".hydra");
+    path == user_config || is_hydra(Some(path)) || is_hydra(path.parent())
+}
+
+/// Watch the config files and emit `config-changed` / `config-error` on edits.
+/// The watched set is re-evaluated after every change, so a project config created
+/// after startup is picked up.
+pub fn spawn(app: AppHandle, store: Arc<ConfigStore>) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let user_config = layers::user_config_path();
+
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if event.paths.iter().any(|p| affects_config(p, &user_config)) {
+                let _ = tx.send(());
+            }
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => {
+            log_error(&format!("Config watcher unavailable: {}", e));
+            return;
+        }
+    };
+
+    // Watch directories rather than files: editors often replace the file instead of writing
+    // in place. The user config dir is created up front so a first config file is noticed.
+    if let Some(user_dir) = layers::user_config_path().parent() {
+        let _ = std::fs::create_dir_all(user_dir);
+    }
+    let mut watched: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut rewatch = move |watcher: &mut notify::RecommendedWatcher| {
+        let dirs = watch_dirs();
+        for dir in watched.difference(&dirs) {
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in dirs.difference(&watched).filter(|d| d.is_dir()) {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                log_error(&format!("Failed to watch {}: {}", dir.display(), e));
+            }
+        }
+        watched = dirs;
+    };
+    rewatch(&mut watcher);
+
+    tauri::async_runtime::spawn(async move {
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            rewatch(&mut watcher);
+
+            match store.reload() {
+                Ok(changes) if changes.is_empty() => {}
+                Ok(changes) => {
+                    let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+                    log_info(&format!("Config reloaded, changed: {}", keys.join(", ")));
+                    let _ = app.emit("config-changed", ConfigChangedEvent { changes });
+                }
+                Err(error) => {
+                    log_error(&format!("Config reload rejected, keeping last good config: {}", error));
+                    let _ = app.emit("config-error", ConfigErrorEvent { error });
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_reports_changed_keys() {
+        let old = json!({"logging": {"level": "info", "rotate_count": 5}, "env": {"A": "1"}});
+        let new = json!({"logging": {"level": "debug", "rotate_count": 5}, "env": {"B": "2"}});
+
+        let keys: Vec<String> = diff(&old, &new).into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, vec!["env.A", "env.B", "logging.level"]);
+
+        let user = Path::new("/home/u/.config/hydra/hydra-config.json");
+        assert!(affects_config(Path::new("/work/.hydra"), user));
+        assert!(affects_config(Path::new("/work/.hydra/config"), user));
+        assert!(!affects_config(Path::new("/work/src/main.rs"), user));
+    }
+}
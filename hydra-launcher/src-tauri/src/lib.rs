@@ -3,33 +3,119 @@ mod commands;
 mod mcp;
 mod process;
 mod logger;
+mod paths;
+mod secrets;
+
+use tauri::{Emitter, Manager, RunEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Must run before the config is loaded into app state
+    let migrated = paths::HydraPaths::resolve().migrate_legacy();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
             commands::check_mcp_health,
+            commands::get_mcp_status,
+            commands::get_mcp_inventory,
+            commands::call_mcp_tool,
+            commands::get_remediation_status,
+            commands::get_health_history,
+            commands::list_mcp_processes,
+            commands::start_mcp,
+            commands::stop_mcp,
+            commands::restart_mcp,
+            commands::tail_mcp_log,
+            commands::check_mcp_ports,
+            commands::reassign_mcp_port,
+            commands::list_child_processes,
+            commands::list_orphan_processes,
+            commands::reap_orphan_processes,
+            commands::adopt_orphan_processes,
             commands::get_system_metrics,
+            commands::get_process_metrics,
             commands::load_hydra_config,
+            commands::get_config_provenance,
+            commands::get_config_error,
+            commands::get_hydra_paths,
+            commands::validate_hydra_config,
+            commands::update_hydra_config,
+            commands::list_hydra_config_backups,
+            commands::restore_hydra_config_backup,
+            commands::export_hydra_bundle,
+            commands::import_hydra_bundle,
+            commands::list_hydra_profiles,
+            commands::activate_hydra_profile,
+            commands::create_hydra_profile,
+            commands::list_hydra_secrets,
+            commands::add_hydra_secret,
+            commands::rotate_hydra_secret,
+            commands::delete_hydra_secret,
             commands::launch_claude,
             commands::check_ollama,
             commands::get_ollama_models,
             commands::set_yolo_mode,
             commands::start_claude_session,
             commands::send_to_claude,
+            commands::send_to_claude_session,
+            commands::read_claude_session_output,
+            commands::stop_claude_session,
         ])
         .manage(commands::AppState::default())
-        .setup(|_app| {
+        .setup(move |app| {
             // Initialize file logger
             if let Err(e) = logger::FileLogger::init() {
                 eprintln!("Failed to init logger: {}", e);
             } else {
-                logger::log_info("HYDRA 10.4 Launcher started");
+                logger::log_info("HYDRA 10.5 Launcher started");
                 logger::log_info("Tauri application setup complete");
             }
+
+            match &migrated {
+                Ok(items) => items.iter().for_each(|item| logger::log_info(&format!("Migrated legacy data: {}", item))),
+                Err(e) => logger::log_error(&format!("Legacy data migration failed: {}", e)),
+            }
+
+            // Hot-reload hydra-config.json into the shared config
+            let store = app.state::<commands::AppState>().config.clone();
+            if let Some(profile) = store.current().launcher.active_profile {
+                logger::log_info(&format!("Active profile: {}", profile));
+            }
+            config::watcher::spawn(app.handle().clone(), store);
+
+            // Leftovers of a crashed run are offered to the UI to reap or adopt
+            let orphans = process::registry::registry().orphans();
+            if !orphans.is_empty() {
+                logger::log_warn(&format!("{} orphaned processes from a previous run", orphans.len()));
+                let _ = app.emit("orphan-processes-found", &orphans);
+            }
+
+            let state = app.state::<commands::AppState>();
+            state.mcp.attach(app.handle().clone());
+            process::metrics::spawn(app.handle().clone());
+            mcp::monitor::spawn(app.handle().clone());
+            if state.config.current().launcher.autostart_mcp {
+                let app = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    commands::start_all_mcp(&app.state::<commands::AppState>()).await;
+                });
+            }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                // Supervised servers first so they aren't restarted mid-cleanup
+                let state = app.state::<commands::AppState>();
+                let _ = state.claude_session.lock().unwrap().stop();
+                let mcp = state.mcp.clone();
+                tauri::async_runtime::block_on(async move {
+                    mcp.stop_all().await;
+                    process::registry::registry().shutdown().await;
+                });
+                logger::log_info("HYDRA Launcher exited");
+            }
+        });
 }
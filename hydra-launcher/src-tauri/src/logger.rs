@@ -3,6 +3,7 @@ use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use chrono::Local;
+use crate::paths::HydraPaths;
 
 /// Global logger instance
 static LOGGER: Mutex<Option<FileLogger>> = Mutex::new(None);
@@ -49,11 +50,7 @@ impl FileLogger {
 
 /// Get the log directory path
 fn get_log_directory() -> Result<PathBuf, String> {
-    let home = std::env::var("USERPROFILE")
-        .or_else(|_| std::env::var("HOME"))
-        .map_err(|_| "Could not determine home directory")?;
-
-    Ok(PathBuf::from(home).join("Desktop").join("ClaudeHYDRA").join("hydra-logs"))
+    Ok(HydraPaths::resolve().log_dir)
 }
 
 /// Log an info message
@@ -64,7 +61,6 @@ pub fn log_info(message: &str) {
 }
 
 /// Log a warning message
-#[allow(dead_code)]
 pub fn log_warn(message: &str) {
     if let Some(logger) = LOGGER.lock().unwrap().as_ref() {
         let _ = logger.write_log("WARN", message);
@@ -0,0 +1,245 @@
+use super::health::check_ready;
+use super::supervisor::{McpProcessInfo, ProcessState, Supervisor};
+use crate::config::{HydraConfig, McpServerConfig};
+use crate::logger::{log_info, log_warn};
+use crate::process::ollama::check_ollama_running;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// `depends_on` entry naming the local Ollama service rather than an MCP server
+pub const OLLAMA: &str = "ollama";
+
+const READY_POLL: Duration = Duration::from_millis(250);
+
+/// A `depends_on` problem, reported against the server that declares it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DependencyIssue {
+    pub server: String,
+    pub message: String,
+}
+
+struct Walk<'a> {
+    servers: &'a BTreeMap<String, McpServerConfig>,
+    stack: Vec<&'a str>,
+    done: BTreeSet<&'a str>,
+    order: Vec<String>,
+    issues: Vec<DependencyIssue>,
+}
+
+impl<'a> Walk<'a> {
+    fn issue(&mut self, server: &str, message: String) {
+        self.issues.push(DependencyIssue { server: server.to_string(), message });
+    }
+
+    /// Depth-first, so every server lands in `order` after its dependencies
+    fn visit(&mut self, name: &'a str) {
+        if self.done.contains(name) {
+            return;
+        }
+        if let Some(i) = self.stack.iter().position(|n| *n == name) {
+            let mut cycle = self.stack[i..].to_vec();
+            cycle.push(name);
+            self.issue(name, format!("dependency cycle: {}", cycle.join(" -> ")));
+            return;
+        }
+
+        let servers = self.servers;
+        self.stack.push(name);
+        for dep in &servers[name].depends_on {
+            match servers.get_key_value(dep) {
+                Some((_, server)) if !server.enabled => {
+                    self.issue(name, format!("depends on disabled MCP server `{}`", dep))
+                }
+                Some((dep, _)) => self.visit(dep),
+                None if dep == OLLAMA => {}
+                None => self.issue(name, format!("depends on unknown MCP server `{}`", dep)),
+            }
+        }
+        self.stack.pop();
+        self.done.insert(name);
+        self.order.push(name.to_string());
+    }
+}
+
+fn walk<'a>(servers: &'a BTreeMap<String, McpServerConfig>, roots: &[&'a str]) -> Walk<'a> {
+    let mut walk = Walk { servers, stack: Vec::new(), done: BTreeSet::new(), order: Vec::new(), issues: Vec::new() };
+    for root in roots {
+        walk.visit(root);
+    }
+    walk
+}
+
+/// Missing, disabled and cyclic dependencies among the enabled servers
+pub fn dependency_issues(servers: &BTreeMap<String, McpServerConfig>) -> Vec<DependencyIssue> {
+    let roots: Vec<&str> = servers.iter().filter(|(_, s)| s.enabled).map(|(n, _)| n.as_str()).collect();
+    walk(servers, &roots).issues
+}
+
+/// `targets` plus everything they depend on, dependencies first
+pub fn start_order(servers: &BTreeMap<String, McpServerConfig>, targets: &[String]) -> Result<Vec<String>, String> {
+    let mut roots = Vec::new();
+    for target in targets {
+        match servers.get_key_value(target) {
+            Some((name, _)) => roots.push(name.as_str()),
+            None => return Err(format!("Unknown MCP server: {}", target)),
+        }
+    }
+    let walk = walk(servers, &roots);
+    if walk.issues.is_empty() {
+        Ok(walk.order)
+    } else {
+        let issues: Vec<String> = walk.issues.iter().map(|i| format!("{}: {}", i.server, i.message)).collect();
+        Err(format!("Invalid MCP dependencies: {}", issues.join("; ")))
+    }
+}
+
+/// Poll `name` until its `ready_when` holds, it exits, or `ready_timeout_seconds` runs out
+async fn wait_ready(mcp: &Supervisor, name: &str, server: &McpServerConfig) -> Result<(), String> {
+    let deadline = Instant::now() + Duration::from_secs(server.ready_timeout_seconds);
+    let mut last = "not started".to_string();
+    loop {
+        let info = mcp.info(name);
+        last = match info.state {
+            ProcessState::Running => match &server.ready_when {
+                Some(condition) => match check_ready(server, condition, mcp.channel(name)).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => e,
+                },
+                None => return Ok(()),
+            },
+            ProcessState::Stopped | ProcessState::Failed => {
+                return Err(format!("exited before ready: {}", info.last_exit.unwrap_or(last)))
+            }
+            _ => last,
+        };
+        if Instant::now() >= deadline {
+            return Err(format!("not ready after {}s: {}", server.ready_timeout_seconds, last));
+        }
+        tokio::time::sleep(READY_POLL).await;
+    }
+}
+
+async fn wait_ollama(url: &str, limit: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + limit;
+    loop {
+        let last = match check_ollama_running(url).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => "not responding".to_string(),
+            Err(e) => e,
+        };
+        if Instant::now() >= deadline {
+            return Err(format!("Ollama not ready after {}s: {}", limit.as_secs(), last));
+        }
+        tokio::time::sleep(READY_POLL).await;
+    }
+}
+
+/// Start `targets` and their dependencies in topological order, holding each server back
+/// until everything it depends on is ready. Servers behind a failed dependency are skipped.
+pub async fn start_in_order(
+    mcp: &Arc<Supervisor>,
+    config: &HydraConfig,
+    targets: &[String],
+) -> Result<BTreeMap<String, Result<McpProcessInfo, String>>, String> {
+    let servers = &config.mcp_tools;
+    let order = start_order(servers, targets)?;
+    let env = config.process_env()?;
+    let needed: BTreeSet<&str> =
+        order.iter().flat_map(|n| servers[n].depends_on.iter().map(String::as_str)).collect();
+
+    let mut results = BTreeMap::new();
+    let mut not_ready: BTreeSet<String> = BTreeSet::new();
+    let mut ollama: Option<Result<(), String>> = None;
+
+    for name in order {
+        let server = &servers[&name];
+        let result = async {
+            if !server.enabled {
+                return Err(format!("MCP server is disabled: {}", name));
+            }
+            for dep in &server.depends_on {
+                if not_ready.contains(dep) {
+                    return Err(format!("dependency `{}` is not ready", dep));
+                }
+                if dep == OLLAMA && !servers.contains_key(OLLAMA) {
+                    let limit = Duration::from_secs(server.ready_timeout_seconds);
+                    if ollama.is_none() {
+                        ollama = Some(wait_ollama(&config.ollama_url(), limit).await);
+                    }
+                    if let Some(Err(e)) = &ollama {
+                        return Err(e.clone());
+                    }
+                }
+            }
+
+            let info = mcp.info(&name);
+            if info.is_active() {
+                Ok(info)
+            } else {
+                mcp.start(&name, server.clone(), env.clone())
+            }
+        }
+        .await;
+
+        let result = match result {
+            Ok(info) if needed.contains(name.as_str()) => match wait_ready(mcp, &name, server).await {
+                Ok(()) => {
+                    log_info(&format!("MCP server '{}' is ready", name));
+                    Ok(mcp.info(&name))
+                }
+                Err(e) => {
+                    log_warn(&format!("MCP server '{}' {}; its dependents are skipped", name, e));
+                    not_ready.insert(name.clone());
+                    Ok(info)
+                }
+            },
+            Ok(info) => Ok(info),
+            Err(e) => {
+                log_warn(&format!("MCP server '{}' not started: {}", name, e));
+                not_ready.insert(name.clone());
+                Err(e)
+            }
+        };
+        results.insert(name, result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_order_and_issues() {
+        let mut config = HydraConfig::default();
+        let template = config.mcp_tools.values().next().unwrap().clone();
+        let server = |deps: &[&str]| McpServerConfig {
+            depends_on: deps.iter().map(|d| d.to_string()).collect(),
+            ..template.clone()
+        };
+        config.mcp_tools = BTreeMap::from([
+            ("app".to_string(), server(&["db", "ollama"])),
+            ("db".to_string(), server(&[])),
+            ("web".to_string(), server(&["app", "db"])),
+        ]);
+        let servers = &config.mcp_tools;
+
+        assert_eq!(start_order(servers, &["web".to_string()]).unwrap(), vec!["db", "app", "web"]);
+        assert_eq!(start_order(servers, &["db".to_string()]).unwrap(), vec!["db"]);
+        assert!(dependency_issues(servers).is_empty());
+
+        config.mcp_tools.insert("db".to_string(), server(&["app"]));
+        config.mcp_tools.insert("lone".to_string(), server(&["nope"]));
+        let issues = dependency_issues(&config.mcp_tools);
+        assert_eq!(
+            issues,
+            vec![
+                DependencyIssue { server: "app".to_string(), message: "dependency cycle: app -> db -> app".to_string() },
+                DependencyIssue { server: "lone".to_string(), message: "depends on unknown MCP server `nope`".to_string() },
+            ]
+        );
+        assert!(start_order(&config.mcp_tools, &["web".to_string()]).unwrap_err().contains("cycle"));
+    }
+}
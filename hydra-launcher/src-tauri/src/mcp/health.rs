@@ -1,84 +1,338 @@
+use super::ports::{self, PortOwnership};
+use super::protocol::{self, McpHandshake, StdioChannel};
+use super::supervisor::{McpProcessInfo, ProcessState, Supervisor};
+use crate::config::{HealthCheckConfig, McpServerConfig, McpTransport, ReadyCondition};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 
+/// Online results slower than this share of the server's timeout are reported degraded
+pub const SLOW_FRACTION: f64 = 0.8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpHealthResult {
     pub name: String,
-    pub port: u16,
+    pub port: Option<u16>,
+    pub strategy: HealthStrategy,
     pub status: McpStatus,
     pub response_time_ms: Option<u64>,
     pub error: Option<String>,
+    /// Server identity from a successful `initialize`
+    pub handshake: Option<McpHandshake>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum McpStatus {
     Online,
+    /// Reachable but not working as expected: slow, wrong HTTP response, or not speaking MCP
+    Degraded,
     Offline,
     Error,
+    /// Stdio server the launcher isn't running, so there is nothing to handshake with
+    Stopped,
+}
+
+/// Health check that produced a result, chosen by the server's `health_check`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStrategy {
+    Port,
+    Http,
+    Process,
+    Mcp,
+}
+
+impl HealthStrategy {
+    pub fn for_server(server: &McpServerConfig) -> Self {
+        match server.health_check {
+            Some(HealthCheckConfig::Port) => Self::Port,
+            Some(HealthCheckConfig::Http { .. }) => Self::Http,
+            Some(HealthCheckConfig::Process { .. }) => Self::Process,
+            Some(HealthCheckConfig::Mcp) | None => Self::Mcp,
+        }
+    }
+}
+
+/// Outcome of a single strategy run
+struct Probe {
+    status: McpStatus,
+    response_time_ms: Option<u64>,
+    error: Option<String>,
+    handshake: Option<McpHandshake>,
+}
+
+impl Probe {
+    fn online(ms: u64) -> Self {
+        Self { status: McpStatus::Online, response_time_ms: Some(ms), error: None, handshake: None }
+    }
+
+    fn degraded(ms: Option<u64>, reason: String) -> Self {
+        Self { status: McpStatus::Degraded, response_time_ms: ms, error: Some(reason), handshake: None }
+    }
+
+    fn offline(reason: String) -> Self {
+        Self { status: McpStatus::Offline, response_time_ms: None, error: Some(reason), handshake: None }
+    }
+
+    fn error(reason: &str) -> Self {
+        Self { status: McpStatus::Error, response_time_ms: None, error: Some(reason.to_string()), handshake: None }
+    }
+
+    fn flag_slow(mut self, timeout_seconds: u64) -> Self {
+        let budget_ms = (timeout_seconds * 1000) as f64 * SLOW_FRACTION;
+        if let (McpStatus::Online, Some(ms)) = (&self.status, self.response_time_ms) {
+            if ms as f64 > budget_ms {
+                self.status = McpStatus::Degraded;
+                self.error = Some(format!("Slow response: {}ms with a {}s timeout", ms, timeout_seconds));
+            }
+        }
+        self
+    }
 }
 
 /// Check if a TCP port is open (basic health check)
-async fn check_port(port: u16) -> Result<u64, String> {
+async fn check_port(port: u16, limit: Duration) -> Result<u64, String> {
     let addr = format!("127.0.0.1:{}", port);
-    let start = std::time::Instant::now();
+    let start = Instant::now();
 
-    match timeout(Duration::from_secs(2), TcpStream::connect(&addr)).await {
+    match timeout(limit, TcpStream::connect(&addr)).await {
         Ok(Ok(_)) => Ok(start.elapsed().as_millis() as u64),
         Ok(Err(e)) => Err(format!("Connection failed: {}", e)),
         Err(_) => Err("Connection timeout".to_string()),
     }
 }
 
-/// Check a single MCP server
-pub async fn check_mcp_server(name: &str, port: u16) -> McpHealthResult {
-    match check_port(port).await {
-        Ok(response_time) => McpHealthResult {
-            name: name.to_string(),
-            port,
-            status: McpStatus::Online,
-            response_time_ms: Some(response_time),
-            error: None,
-        },
-        Err(e) => McpHealthResult {
-            name: name.to_string(),
-            port,
-            status: McpStatus::Offline,
-            response_time_ms: None,
-            error: Some(e),
+/// Port to probe: `health_port`, else the port of `health_url`
+fn probe_port(server: &McpServerConfig) -> Option<u16> {
+    server.health_port.or_else(|| {
+        let url = reqwest::Url::parse(server.health_url.as_deref()?).ok()?;
+        url.port_or_known_default()
+    })
+}
+
+/// TCP connect to the server's port
+async fn run_port(server: &McpServerConfig) -> Probe {
+    let Some(port) = probe_port(server) else {
+        return Probe::error("No health_port or health_url configured");
+    };
+    match check_port(port, Duration::from_secs(server.timeout_seconds)).await {
+        Ok(ms) => Probe::online(ms),
+        Err(e) => Probe::offline(e),
+    }
+}
+
+/// GET `health_url` and compare the status and body
+async fn run_http(server: &McpServerConfig, expect_status: u16, expect_body: Option<&str>) -> Probe {
+    let Some(url) = server.health_url.as_deref() else {
+        return Probe::error("No health_url configured");
+    };
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(server.timeout_seconds)).build() {
+        Ok(client) => client,
+        Err(e) => return Probe::error(&format!("HTTP client unavailable: {}", e)),
+    };
+
+    let start = Instant::now();
+    let response = match client.get(url).send().await {
+        Ok(response) => response,
+        Err(e) if e.is_timeout() => return Probe::offline("Request timeout".to_string()),
+        Err(e) => return Probe::offline(format!("Request failed: {}", e)),
+    };
+    let ms = start.elapsed().as_millis() as u64;
+
+    let status = response.status().as_u16();
+    if status != expect_status {
+        return Probe::degraded(Some(ms), format!("Unexpected status {} (expected {})", status, expect_status));
+    }
+    if let Some(expected) = expect_body {
+        let body = response.text().await.unwrap_or_default();
+        if !body.contains(expected) {
+            return Probe::degraded(Some(ms), format!("Response does not contain `{}`", expected));
+        }
+    }
+    Probe::online(ms)
+}
+
+/// Look for a running process whose name or command line contains `name`
+async fn run_process(name: &str) -> Probe {
+    let needle = name.to_lowercase();
+    let start = Instant::now();
+    let found = tokio::task::spawn_blocking(move || {
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+        sys.processes().values().any(|p| {
+            p.name().to_string_lossy().to_lowercase().contains(&needle)
+                || p.cmd().iter().any(|arg| arg.to_string_lossy().to_lowercase().contains(&needle))
+        })
+    })
+    .await;
+
+    match found {
+        Ok(true) => Probe::online(start.elapsed().as_millis() as u64),
+        Ok(false) => Probe::offline(format!("No running process matching `{}`", name)),
+        Err(e) => Probe::error(&format!("Process scan failed: {}", e)),
+    }
+}
+
+/// `initialize` + `ping` over the server's transport. Network transports get a port
+/// check first so a stopped server fails fast; an open port that doesn't speak MCP is degraded.
+/// Stdio servers are probed through the supervised process and are offline without one.
+async fn run_mcp(server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> Probe {
+    let network = server.transport() != McpTransport::Stdio;
+    if network {
+        let port = run_port(server).await;
+        if port.status != McpStatus::Online {
+            return port;
+        }
+    }
+
+    let start = Instant::now();
+    match protocol::probe(server, channel).await {
+        Ok(handshake) => Probe {
+            handshake: Some(handshake),
+            ..Probe::online(start.elapsed().as_millis() as u64)
         },
+        Err(e) if network => Probe::degraded(None, format!("Port open but MCP handshake failed: {}", e)),
+        Err(e) => Probe::offline(e),
     }
 }
 
-/// Check all configured MCP servers in parallel
-pub async fn check_all_mcp_servers() -> Result<Vec<McpHealthResult>, String> {
-    let servers = vec![
-        ("Serena", 9000u16),
-        ("Desktop Commander", 8100u16),
-        ("Playwright", 5200u16),
-    ];
+/// Check a single MCP server with its configured strategy; `channel` reaches a running stdio server
+pub async fn check_mcp_server(name: &str, server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> McpHealthResult {
+    let mut probe = match &server.health_check {
+        Some(HealthCheckConfig::Port) => run_port(server).await,
+        Some(HealthCheckConfig::Http { expect_status, expect_body }) => {
+            run_http(server, *expect_status, expect_body.as_deref()).await
+        }
+        Some(HealthCheckConfig::Process { name }) => run_process(name).await,
+        Some(HealthCheckConfig::Mcp) | None => run_mcp(server, channel).await,
+    }
+    .flag_slow(server.timeout_seconds);
+
+    // An answering port only counts if the server is the one listening on it
+    let reachable = matches!(probe.status, McpStatus::Online | McpStatus::Degraded);
+    if !reachable {
+        ports::forget_port_owner(name);
+    } else if HealthStrategy::for_server(server) != HealthStrategy::Process {
+        if let Some(check) = ports::known_port_owner(name, server).await {
+            if check.ownership == PortOwnership::Foreign {
+                probe.status = McpStatus::Degraded;
+                probe.error = check.message;
+            }
+        }
+    }
 
+    McpHealthResult {
+        name: name.to_string(),
+        port: probe_port(server),
+        strategy: HealthStrategy::for_server(server),
+        status: probe.status,
+        response_time_ms: probe.response_time_ms,
+        error: probe.error,
+        handshake: probe.handshake,
+    }
+}
+
+/// Whether the configured check talks to the server over its supervised stdio pipes
+fn needs_channel(server: &McpServerConfig) -> bool {
+    HealthStrategy::for_server(server) == HealthStrategy::Mcp && server.transport() == McpTransport::Stdio
+}
+
+/// Result for a stdio server the handshake can't reach: stopped, or offline with the
+/// supervisor state or exit reason
+pub fn not_running(name: &str, server: &McpServerConfig, info: &McpProcessInfo) -> McpHealthResult {
+    let (status, error) = match info.state {
+        ProcessState::Stopped => (McpStatus::Stopped, info.last_exit.clone()),
+        ProcessState::Failed => (McpStatus::Offline, info.last_exit.clone().or_else(|| Some("Exited".to_string()))),
+        ProcessState::Backoff => (
+            McpStatus::Offline,
+            Some(format!("Restarting: {}", info.last_exit.as_deref().unwrap_or("exited"))),
+        ),
+        ProcessState::Starting | ProcessState::Running | ProcessState::Stopping => {
+            (McpStatus::Offline, Some(format!("Server is {}", format!("{:?}", info.state).to_lowercase())))
+        }
+    };
+    McpHealthResult {
+        name: name.to_string(),
+        port: probe_port(server),
+        strategy: HealthStrategy::for_server(server),
+        status,
+        response_time_ms: None,
+        error,
+        handshake: None,
+    }
+}
+
+/// Check a server whatever the supervisor is doing with it: port, HTTP, process and
+/// network MCP checks always run, since the server may be started outside the launcher;
+/// only a stdio handshake needs the supervised process
+pub async fn check_supervised(
+    name: &str,
+    server: &McpServerConfig,
+    info: &McpProcessInfo,
+    channel: Option<Arc<StdioChannel>>,
+) -> McpHealthResult {
+    match channel {
+        None if needs_channel(server) => not_running(name, server, info),
+        channel => check_mcp_server(name, server, channel).await,
+    }
+}
+
+/// One readiness attempt for a running server; `Err` carries why it isn't ready yet
+pub async fn check_ready(
+    server: &McpServerConfig,
+    condition: &ReadyCondition,
+    channel: Option<Arc<StdioChannel>>,
+) -> Result<(), String> {
+    let probe = match condition {
+        ReadyCondition::Port => run_port(server).await,
+        ReadyCondition::Http { expect_status } => run_http(server, *expect_status, None).await,
+        ReadyCondition::Mcp => run_mcp(server, channel).await,
+    };
+    match probe.status {
+        McpStatus::Online => Ok(()),
+        _ => Err(probe.error.unwrap_or_else(|| "not ready".to_string())),
+    }
+}
+
+/// Check all enabled MCP servers from the config in parallel
+pub async fn check_all_mcp_servers(
+    servers: &BTreeMap<String, McpServerConfig>,
+    mcp: &Supervisor,
+) -> Result<Vec<McpHealthResult>, String> {
     let handles: Vec<_> = servers
-        .into_iter()
-        .map(|(name, port)| {
-            let name = name.to_string();
-            tokio::spawn(async move { check_mcp_server(&name, port).await })
+        .iter()
+        .filter(|(_, server)| server.enabled)
+        .map(|(name, server)| {
+            let strategy = HealthStrategy::for_server(server);
+            let info = mcp.info(name);
+            let (task_name, server, channel) = (name.clone(), server.clone(), mcp.channel(name));
+            let task = tokio::spawn(async move { check_supervised(&task_name, &server, &info, channel).await });
+            (name.clone(), strategy, task)
         })
         .collect();
 
     let mut results = Vec::new();
-    for handle in handles {
+    for (name, strategy, handle) in handles {
         match handle.await {
             Ok(result) => results.push(result),
             Err(e) => {
                 results.push(McpHealthResult {
-                    name: "Unknown".to_string(),
-                    port: 0,
+                    name,
+                    port: None,
+                    strategy,
                     status: McpStatus::Error,
                     response_time_ms: None,
                     error: Some(format!("Task failed: {}", e)),
+                    handshake: None,
                 });
             }
         }
@@ -94,7 +348,14 @@ mod tests {
     #[tokio::test]
     async fn test_check_port_timeout() {
         // Port that's unlikely to be open
-        let result = check_port(59999).await;
+        let result = check_port(59999, Duration::from_secs(2)).await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_slow_online_is_degraded() {
+        assert_eq!(Probe::online(4500).flag_slow(5).status, McpStatus::Degraded);
+        assert_eq!(Probe::online(100).flag_slow(5).status, McpStatus::Online);
+        assert_eq!(Probe::offline("down".to_string()).flag_slow(5).status, McpStatus::Offline);
+    }
 }
@@ -0,0 +1,252 @@
+use super::health::McpStatus;
+use crate::config::edit::write_atomic;
+use crate::logger::log_error;
+use crate::paths::HydraPaths;
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// History target for the local Ollama service
+pub const OLLAMA_TARGET: &str = "ollama";
+/// History target for the Claude CLI being installed and on PATH
+pub const CLAUDE_TARGET: &str = "claude-cli";
+
+/// How often old samples are pruned from the store
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// One probe, stored as a JSON line in `<data_dir>/health/<target>.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthSample {
+    /// Unix time in milliseconds
+    #[serde(rename = "t")]
+    pub at_ms: i64,
+    #[serde(rename = "s")]
+    pub status: McpStatus,
+    #[serde(rename = "ms", default, skip_serializing_if = "Option::is_none")]
+    pub response_time_ms: Option<u64>,
+}
+
+impl HealthSample {
+    pub fn now(status: McpStatus, response_time_ms: Option<u64>) -> Self {
+        Self { at_ms: Local::now().timestamp_millis(), status, response_time_ms }
+    }
+
+    /// Online and degraded both count as available
+    fn is_up(&self) -> bool {
+        matches!(self.status, McpStatus::Online | McpStatus::Degraded)
+    }
+}
+
+/// A run of offline/error samples
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Outage {
+    pub start: String,
+    /// First available sample after the outage; `None` while it lasts
+    pub end: Option<String>,
+    pub duration_seconds: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LatencyPercentiles {
+    pub p50: Option<u64>,
+    pub p95: Option<u64>,
+    pub p99: Option<u64>,
+}
+
+/// Payload of `get_health_history`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthHistoryReport {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub samples: usize,
+    /// Share of samples that were online or degraded; `None` without samples
+    pub uptime_percent: Option<f64>,
+    /// Over available samples only
+    pub latency_ms: LatencyPercentiles,
+    pub outages: Vec<Outage>,
+}
+
+/// Append-only probe store with day-based retention
+pub struct HealthHistory {
+    dir: PathBuf,
+    last_prune: Mutex<Option<Instant>>,
+}
+
+impl Default for HealthHistory {
+    fn default() -> Self {
+        Self::open(HydraPaths::resolve().data_dir.join("health"))
+    }
+}
+
+fn rfc3339(ms: i64) -> String {
+    Local.timestamp_millis_opt(ms).single().map(|t| t.to_rfc3339()).unwrap_or_default()
+}
+
+/// Parse a range such as `30m`, `24h` or `7d`
+pub fn parse_range(range: &str) -> Result<Duration, String> {
+    let range = range.trim();
+    let split = range.find(|c: char| !c.is_ascii_digit()).unwrap_or(range.len());
+    let (value, unit) = range.split_at(split);
+    let value: u64 = value.parse().map_err(|_| format!("Invalid range: {}", range))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" | "" => 3600,
+        "d" => 86_400,
+        _ => return Err(format!("Invalid range unit in {} (use s, m, h or d)", range)),
+    };
+    Ok(Duration::from_secs(value * seconds))
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len()) - 1).copied()
+}
+
+/// Uptime, latency percentiles and outages of `samples` (oldest first) between `from_ms` and `to_ms`
+pub fn summarize(name: &str, samples: &[HealthSample], from_ms: i64, to_ms: i64) -> HealthHistoryReport {
+    let samples: Vec<&HealthSample> = samples.iter().filter(|s| s.at_ms >= from_ms && s.at_ms <= to_ms).collect();
+    let up = samples.iter().filter(|s| s.is_up()).count();
+
+    let mut latencies: Vec<u64> = samples.iter().filter(|s| s.is_up()).filter_map(|s| s.response_time_ms).collect();
+    latencies.sort_unstable();
+
+    let mut outages = Vec::new();
+    let mut down_since: Option<i64> = None;
+    for sample in &samples {
+        match (sample.is_up(), down_since) {
+            (false, None) => down_since = Some(sample.at_ms),
+            (true, Some(start)) => {
+                outages.push(Outage {
+                    start: rfc3339(start),
+                    end: Some(rfc3339(sample.at_ms)),
+                    duration_seconds: ((sample.at_ms - start) / 1000) as u64,
+                });
+                down_since = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = down_since {
+        outages.push(Outage { start: rfc3339(start), end: None, duration_seconds: ((to_ms - start).max(0) / 1000) as u64 });
+    }
+
+    HealthHistoryReport {
+        name: name.to_string(),
+        from: rfc3339(from_ms),
+        to: rfc3339(to_ms),
+        samples: samples.len(),
+        uptime_percent: (!samples.is_empty()).then(|| up as f64 * 100.0 / samples.len() as f64),
+        latency_ms: LatencyPercentiles {
+            p50: percentile(&latencies, 50.0),
+            p95: percentile(&latencies, 95.0),
+            p99: percentile(&latencies, 99.0),
+        },
+        outages,
+    }
+}
+
+impl HealthHistory {
+    fn open(dir: PathBuf) -> Self {
+        Self { dir, last_prune: Mutex::new(None) }
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        let file: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.jsonl", file))
+    }
+
+    /// Persist one probe, pruning samples older than `retention_days` at most once an hour
+    pub fn append(&self, name: &str, sample: &HealthSample, retention_days: u32) {
+        let mut last_prune = self.last_prune.lock().unwrap();
+        if last_prune.map_or(true, |t| t.elapsed() >= PRUNE_INTERVAL) {
+            *last_prune = Some(Instant::now());
+            self.prune(retention_days);
+        }
+
+        let line = format!("{}\n", serde_json::to_string(sample).unwrap_or_default());
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(self.file(name)))
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(e) = result {
+            log_error(&format!("Failed to record health sample for {}: {}", name, e));
+        }
+    }
+
+    fn read(path: &Path) -> Vec<HealthSample> {
+        fs::read_to_string(path)
+            .map(|content| content.lines().filter_map(|l| serde_json::from_str(l).ok()).collect())
+            .unwrap_or_default()
+    }
+
+    fn prune(&self, retention_days: u32) {
+        let cutoff = Local::now().timestamp_millis() - i64::from(retention_days) * 86_400_000;
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let samples = Self::read(&path);
+            let kept: Vec<&HealthSample> = samples.iter().filter(|s| s.at_ms >= cutoff).collect();
+            if kept.len() == samples.len() {
+                continue;
+            }
+            if kept.is_empty() {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            let content: String =
+                kept.iter().map(|s| format!("{}\n", serde_json::to_string(s).unwrap_or_default())).collect();
+            if let Err(e) = write_atomic(&path, &content) {
+                log_error(&format!("Failed to prune health history: {}", e));
+            }
+        }
+    }
+
+    /// Summary of `name` over the last `range`
+    pub fn report(&self, name: &str, range: Duration) -> HealthHistoryReport {
+        let to_ms = Local::now().timestamp_millis();
+        let from_ms = to_ms - range.as_millis() as i64;
+        let _guard = self.last_prune.lock().unwrap();
+        summarize(name, &Self::read(&self.file(name)), from_ms, to_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_uptime_percentiles_and_outages() {
+        let sample = |sec: i64, status, ms| HealthSample { at_ms: sec * 1000, status, response_time_ms: ms };
+        let mut samples: Vec<HealthSample> = (1..=100).map(|i| sample(i, McpStatus::Online, Some(i as u64))).collect();
+        samples.push(sample(101, McpStatus::Offline, None));
+        samples.push(sample(131, McpStatus::Error, None));
+        samples.push(sample(161, McpStatus::Degraded, Some(500)));
+        samples.push(sample(191, McpStatus::Offline, None));
+
+        let report = summarize("serena", &samples, 0, 200_000);
+        assert_eq!(report.samples, 104);
+        assert_eq!(report.uptime_percent, Some(101.0 * 100.0 / 104.0));
+        assert_eq!(report.latency_ms, LatencyPercentiles { p50: Some(51), p95: Some(96), p99: Some(100) });
+        assert_eq!(report.outages.len(), 2);
+        assert_eq!(report.outages[0].duration_seconds, 60);
+        assert!(report.outages[0].end.is_some());
+        assert_eq!((report.outages[1].duration_seconds, report.outages[1].end.as_ref()), (9, None));
+
+        assert_eq!(parse_range("24h").unwrap(), Duration::from_secs(86_400));
+        assert_eq!(parse_range("7d").unwrap(), Duration::from_secs(7 * 86_400));
+        assert!(parse_range("5w").is_err());
+    }
+}
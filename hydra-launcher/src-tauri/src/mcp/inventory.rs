@@ -0,0 +1,278 @@
+use super::protocol::{McpClient, StdioChannel};
+use crate::config::edit::write_atomic;
+use crate::config::{McpServerConfig, McpTransport};
+use crate::logger::{log_error, log_warn};
+use crate::paths::HydraPaths;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Upper bound on `nextCursor` pages per list, against servers that never stop paginating
+const MAX_PAGES: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpTool {
+    pub name: String,
+    pub description: Option<String>,
+    /// JSON Schema of the tool's arguments
+    pub input_schema: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpPrompt {
+    pub name: String,
+    pub description: Option<String>,
+    /// `arguments` as listed by the server
+    pub arguments: Value,
+}
+
+/// What one server offers, from `get_mcp_inventory`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpInventory {
+    pub server: String,
+    pub fetched_at: String,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub tools: Vec<McpTool>,
+    pub resources: Vec<McpResource>,
+    pub prompts: Vec<McpPrompt>,
+    /// Configured `key_tools` the server no longer lists
+    pub missing_key_tools: Vec<String>,
+    /// Why the last refresh failed; the lists are from the last successful one
+    pub error: Option<String>,
+}
+
+impl McpInventory {
+    fn empty(server: &str) -> Self {
+        Self {
+            server: server.to_string(),
+            fetched_at: Local::now().to_rfc3339(),
+            server_name: None,
+            server_version: None,
+            tools: Vec::new(),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            missing_key_tools: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+fn text(item: &Value, key: &str) -> Option<String> {
+    item.get(key).and_then(Value::as_str).map(String::from)
+}
+
+fn parse_tool(item: &Value) -> Option<McpTool> {
+    Some(McpTool {
+        name: text(item, "name")?,
+        description: text(item, "description"),
+        input_schema: item.get("inputSchema").cloned().unwrap_or_else(|| json!({ "type": "object" })),
+    })
+}
+
+fn parse_resource(item: &Value) -> Option<McpResource> {
+    let uri = text(item, "uri")?;
+    Some(McpResource {
+        name: text(item, "name").unwrap_or_else(|| uri.clone()),
+        uri,
+        description: text(item, "description"),
+        mime_type: text(item, "mimeType"),
+    })
+}
+
+fn parse_prompt(item: &Value) -> Option<McpPrompt> {
+    Some(McpPrompt {
+        name: text(item, "name")?,
+        description: text(item, "description"),
+        arguments: item.get("arguments").cloned().unwrap_or_else(|| json!([])),
+    })
+}
+
+/// Every item of a paginated `*/list` method; empty when the server doesn't declare `capability`
+async fn list_all(client: &mut McpClient, capability: &str, method: &str, key: &str) -> Result<Vec<Value>, String> {
+    if client.capabilities().get(capability).is_none() {
+        return Ok(Vec::new());
+    }
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let page = client.request(method, params).await?;
+        items.extend(page.get(key).and_then(Value::as_array).cloned().unwrap_or_default());
+        cursor = text(&page, "nextCursor");
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// Every tool the connected server offers
+pub async fn list_tools(client: &mut McpClient) -> Result<Vec<McpTool>, String> {
+    Ok(list_all(client, "tools", "tools/list", "tools").await?.iter().filter_map(parse_tool).collect())
+}
+
+/// `key_tools` entries missing from `tools`
+pub fn missing_key_tools(server: &McpServerConfig, tools: &[McpTool]) -> Vec<String> {
+    server.key_tools.iter().filter(|k| !tools.iter().any(|t| &t.name == *k)).cloned().collect()
+}
+
+/// Connect to the server and list its tools, resources and prompts
+pub async fn fetch(name: &str, server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> Result<McpInventory, String> {
+    let mut client = McpClient::connect(server, channel).await?;
+    let lists = async {
+        let tools = list_tools(&mut client).await?;
+        let resources = list_all(&mut client, "resources", "resources/list", "resources").await?;
+        let prompts = list_all(&mut client, "prompts", "prompts/list", "prompts").await?;
+        Ok::<_, String>((tools, resources, prompts))
+    }
+    .await;
+    let handshake = client.handshake(0);
+    client.close().await;
+    let (tools, resources, prompts) = lists?;
+
+    let handshake = handshake.ok();
+    Ok(McpInventory {
+        missing_key_tools: missing_key_tools(server, &tools),
+        server_name: handshake.as_ref().map(|h| h.server_name.clone()),
+        server_version: handshake.and_then(|h| h.server_version),
+        tools,
+        resources: resources.iter().filter_map(parse_resource).collect(),
+        prompts: prompts.iter().filter_map(parse_prompt).collect(),
+        ..McpInventory::empty(name)
+    })
+}
+
+/// Last inventory of every server, persisted to `<cache_dir>/mcp-inventory.json`
+pub struct InventoryCache {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, McpInventory>>,
+}
+
+impl Default for InventoryCache {
+    fn default() -> Self {
+        let path = HydraPaths::resolve().cache_dir.join("mcp-inventory.json");
+        let entries = fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str(&c).ok()).unwrap_or_default();
+        Self { path, entries: Mutex::new(entries) }
+    }
+}
+
+impl InventoryCache {
+    pub fn get(&self, name: &str) -> Option<McpInventory> {
+        self.entries.lock().unwrap().get(name).cloned()
+    }
+
+    /// Cached inventories of `servers`, with `missing_key_tools` re-checked against the current config.
+    /// Enabled servers that were never listed get an entry saying why.
+    pub fn list(&self, servers: &BTreeMap<String, McpServerConfig>) -> Vec<McpInventory> {
+        let entries = self.entries.lock().unwrap();
+        servers
+            .iter()
+            .filter_map(|(name, server)| {
+                let Some(inventory) = entries.get(name) else {
+                    return server.enabled.then(|| McpInventory {
+                        error: Some("Not listed yet: the server is not running".to_string()),
+                        ..McpInventory::empty(name)
+                    });
+                };
+                let mut inventory = inventory.clone();
+                // A server that was never listed successfully isn't missing anything yet
+                if inventory.error.is_none() || !inventory.tools.is_empty() {
+                    inventory.missing_key_tools = missing_key_tools(server, &inventory.tools);
+                }
+                Some(inventory)
+            })
+            .collect()
+    }
+
+    /// Fetch `name` again; on failure the previous lists are kept alongside the error
+    pub async fn refresh(&self, name: &str, server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> McpInventory {
+        let inventory = match fetch(name, server, channel).await {
+            Ok(inventory) => {
+                if !inventory.missing_key_tools.is_empty() {
+                    log_warn(&format!(
+                        "MCP {}: key_tools not offered by the server: {}",
+                        name,
+                        inventory.missing_key_tools.join(", ")
+                    ));
+                }
+                inventory
+            }
+            Err(e) => McpInventory {
+                error: Some(e),
+                fetched_at: Local::now().to_rfc3339(),
+                ..self.get(name).unwrap_or_else(|| McpInventory::empty(name))
+            },
+        };
+
+        self.store(name, inventory.clone());
+        inventory
+    }
+
+    /// Replace the tools of `name` with a fresher `tools/list`, keeping its other lists
+    pub fn update_tools(&self, name: &str, server: &McpServerConfig, tools: Vec<McpTool>) {
+        let inventory = McpInventory {
+            missing_key_tools: missing_key_tools(server, &tools),
+            tools,
+            fetched_at: Local::now().to_rfc3339(),
+            ..self.get(name).unwrap_or_else(|| McpInventory::empty(name))
+        };
+        self.store(name, inventory);
+    }
+
+    fn store(&self, name: &str, inventory: McpInventory) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(name.to_string(), inventory);
+        let saved = fs::create_dir_all(self.path.parent().unwrap_or(&self.path))
+            .map_err(|e| e.to_string())
+            .and_then(|_| write_atomic(&self.path, &serde_json::to_string_pretty(&*entries).unwrap_or_default()));
+        if let Err(e) = saved {
+            log_error(&format!("Failed to save MCP inventory: {}", e));
+        }
+    }
+}
+
+/// Whether the server can be listed: stdio servers only while their supervised process is
+/// up, network servers whenever they answer
+pub fn listable(server: &McpServerConfig, running: bool) -> bool {
+    server.enabled && (running || server.transport() != McpTransport::Stdio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HydraConfig;
+
+    #[test]
+    fn test_parse_and_missing_key_tools() {
+        let page = json!({ "tools": [
+            { "name": "browser_navigate", "inputSchema": { "type": "object", "required": ["url"] } },
+            { "name": "browser_type", "description": "Type text" },
+            { "description": "no name" }
+        ]});
+        let tools: Vec<McpTool> = page["tools"].as_array().unwrap().iter().filter_map(parse_tool).collect();
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[1].input_schema, json!({ "type": "object" }));
+
+        let server = McpServerConfig {
+            key_tools: vec!["browser_navigate".into(), "browser_fill".into()],
+            ..HydraConfig::default().mcp_tools.values().next().unwrap().clone()
+        };
+        assert_eq!(missing_key_tools(&server, &tools), vec!["browser_fill"]);
+    }
+}
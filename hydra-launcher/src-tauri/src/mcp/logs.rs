@@ -0,0 +1,180 @@
+use super::protocol::StdioChannel;
+use crate::paths::HydraPaths;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+/// Size at which a server log is rotated to `<name>.log.1`
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept per server (`<name>.log.1` … `<name>.log.N`)
+const KEEP_ROTATED: usize = 3;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of the `mcp-log` event, one per captured line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpLogLine {
+    pub server: String,
+    pub stream: LogStream,
+    pub line: String,
+    pub timestamp: String,
+}
+
+/// Directory holding the per-server logs
+pub fn mcp_log_dir() -> PathBuf {
+    HydraPaths::resolve().log_dir.join("mcp")
+}
+
+/// Log file of a server; config keys are sanitised so they stay inside the log directory
+fn log_path(dir: &Path, name: &str) -> PathBuf {
+    let file: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    dir.join(format!("{}.log", file))
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), n))
+}
+
+/// Append-only log file that rolls over at `MAX_LOG_BYTES`
+pub struct ServerLog {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl ServerLog {
+    pub fn open(name: &str) -> Self {
+        Self::open_in(&mcp_log_dir(), name, MAX_LOG_BYTES)
+    }
+
+    fn open_in(dir: &Path, name: &str, max_bytes: u64) -> Self {
+        let path = log_path(dir, name);
+        let _ = fs::create_dir_all(dir);
+        let file = OpenOptions::new().create(true).append(true).open(&path).ok();
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self { path, file, size, max_bytes }
+    }
+
+    fn write_line(&mut self, stream: LogStream, timestamp: &str, line: &str) {
+        if self.size >= self.max_bytes {
+            self.rotate();
+        }
+        let tag = match stream {
+            LogStream::Stdout => "out",
+            LogStream::Stderr => "err",
+        };
+        let entry = format!("[{}] [{}] {}\n", timestamp, tag, line);
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(entry.as_bytes()).is_ok() {
+                self.size += entry.len() as u64;
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        for n in (1..KEEP_ROTATED).rev() {
+            let _ = fs::rename(rotated_path(&self.path, n), rotated_path(&self.path, n + 1));
+        }
+        let _ = fs::rename(&self.path, rotated_path(&self.path, 1));
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path).ok();
+        self.size = 0;
+    }
+}
+
+/// Copy a child's output into its server log line by line, emitting `mcp-log` as it goes.
+/// Lines `channel` takes as JSON-RPC traffic are not logged.
+pub async fn capture(
+    app: Option<AppHandle>,
+    log: Arc<Mutex<ServerLog>>,
+    server: String,
+    stream: LogStream,
+    output: impl AsyncRead + Unpin,
+    channel: Option<Arc<StdioChannel>>,
+) {
+    let mut lines = BufReader::new(output).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(channel) = &channel {
+            if channel.dispatch(&line).await {
+                continue;
+            }
+        }
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        log.lock().unwrap().write_line(stream, &timestamp, &line);
+        if let Some(app) = &app {
+            let _ = app.emit("mcp-log", McpLogLine { server: server.clone(), stream, line, timestamp });
+        }
+    }
+}
+
+/// Last `lines` lines of a server's log, reaching into rotated files when the current one is short
+pub fn tail(name: &str, lines: usize) -> Result<Vec<String>, String> {
+    tail_in(&mcp_log_dir(), name, lines)
+}
+
+fn tail_in(dir: &Path, name: &str, lines: usize) -> Result<Vec<String>, String> {
+    let path = log_path(dir, name);
+    let mut tail: Vec<String> = Vec::new();
+    let mut found = false;
+
+    for n in 0..=KEEP_ROTATED {
+        if tail.len() >= lines {
+            break;
+        }
+        let file = if n == 0 { path.clone() } else { rotated_path(&path, n) };
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        found = true;
+        let mut older: Vec<String> = content.lines().rev().take(lines - tail.len()).map(String::from).collect();
+        older.reverse();
+        older.append(&mut tail);
+        tail = older;
+    }
+
+    if !found {
+        return Err(format!("No log captured for MCP server: {}", name));
+    }
+    Ok(tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_and_tail_across_files() {
+        let dir = std::env::temp_dir().join(format!("hydra-mcp-logs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut log = ServerLog::open_in(&dir, "serena/../x", 100);
+        for i in 0..10 {
+            log.write_line(LogStream::Stderr, "t", &format!("line {}", i));
+        }
+        drop(log);
+
+        assert!(dir.join("serena____x.log").exists());
+        assert!(dir.join("serena____x.log.1").exists());
+        let tail = tail_in(&dir, "serena/../x", 6).unwrap();
+        assert_eq!(tail.len(), 6);
+        assert_eq!(tail.first().unwrap(), "[t] [err] line 4");
+        assert_eq!(tail.last().unwrap(), "[t] [err] line 9");
+        assert!(tail_in(&dir, "missing", 5).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
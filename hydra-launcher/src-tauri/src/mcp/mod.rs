@@ -1,2 +1,12 @@
+pub mod deps;
 pub mod health;
+pub mod history;
+pub mod inventory;
+pub mod logs;
+pub mod monitor;
+pub mod ports;
+pub mod protocol;
+pub mod remediation;
 pub mod server;
+pub mod supervisor;
+pub mod tools;
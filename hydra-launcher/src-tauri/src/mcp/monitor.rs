@@ -0,0 +1,191 @@
+use super::health::{check_supervised, McpHealthResult, McpStatus, SLOW_FRACTION};
+use super::history::{HealthHistory, HealthSample, CLAUDE_TARGET, OLLAMA_TARGET};
+use super::remediation::remediate;
+use crate::commands::AppState;
+use crate::config::McpServerConfig;
+use crate::logger::log_mcp_health;
+use crate::process::claude::check_claude_installed;
+use crate::process::ollama::check_ollama_running;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Granularity of the per-server schedule
+const TICK: Duration = Duration::from_secs(1);
+/// How often Ollama and Claude CLI availability are sampled for the history
+const SERVICE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Payload of `mcp-status-changed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpStatusChange {
+    pub name: String,
+    /// `None` for the first result after startup or a config change
+    pub previous: Option<McpStatus>,
+    pub was_slow: bool,
+    /// Response time is above the server's `latency_threshold_ms`
+    pub slow: bool,
+    pub result: McpHealthResult,
+}
+
+struct Known {
+    result: McpHealthResult,
+    slow: bool,
+    next_due: Instant,
+}
+
+/// Checks each enabled server on its own interval and reports only transitions
+#[derive(Default)]
+pub struct HealthMonitor {
+    app: OnceLock<AppHandle>,
+    known: Mutex<BTreeMap<String, Known>>,
+    pub history: HealthHistory,
+}
+
+fn latency_threshold_ms(server: &McpServerConfig) -> u64 {
+    server
+        .latency_threshold_ms
+        .unwrap_or(((server.timeout_seconds * 1000) as f64 * SLOW_FRACTION) as u64)
+}
+
+fn status_label(status: &McpStatus) -> &'static str {
+    match status {
+        McpStatus::Online => "HEALTHY",
+        McpStatus::Degraded => "DEGRADED",
+        McpStatus::Offline | McpStatus::Error => "DOWN",
+        McpStatus::Stopped => "STOPPED",
+    }
+}
+
+impl HealthMonitor {
+    /// Last known result of every monitored server
+    pub fn snapshot(&self) -> Vec<McpHealthResult> {
+        self.known.lock().unwrap().values().map(|k| k.result.clone()).collect()
+    }
+
+    /// Store a result in the history, logging and emitting `mcp-status-changed` only when
+    /// the status or the slow flag differs from the previous one. Stopped servers aren't
+    /// down, so they leave no sample.
+    pub fn record(&self, server: &McpServerConfig, result: McpHealthResult, retention_days: u32) {
+        if result.status != McpStatus::Stopped {
+            let sample = HealthSample::now(result.status.clone(), result.response_time_ms);
+            self.history.append(&result.name, &sample, retention_days);
+        }
+
+        let slow = result.response_time_ms.is_some_and(|ms| ms > latency_threshold_ms(server));
+        let next_due = Instant::now() + Duration::from_secs(server.health_interval_seconds.max(1));
+
+        let change = {
+            let mut known = self.known.lock().unwrap();
+            let previous = known.insert(
+                result.name.clone(),
+                Known { result: result.clone(), slow, next_due },
+            );
+            match previous {
+                Some(prev) if prev.result.status == result.status && prev.slow == slow => None,
+                prev => Some(McpStatusChange {
+                    name: result.name.clone(),
+                    previous: prev.as_ref().map(|p| p.result.status.clone()),
+                    was_slow: prev.is_some_and(|p| p.slow),
+                    slow,
+                    result,
+                }),
+            }
+        };
+        let Some(change) = change else {
+            return;
+        };
+
+        let status = match (&change.previous, change.slow) {
+            (Some(prev), _) if *prev != change.result.status => {
+                format!("{} -> {}", status_label(prev), status_label(&change.result.status))
+            }
+            (_, true) => format!("{} (slow)", status_label(&change.result.status)),
+            _ => status_label(&change.result.status).to_string(),
+        };
+        match &change.result.error {
+            Some(e) => log_mcp_health(&change.name, &format!("{}: {}", status, e), change.result.response_time_ms),
+            None => log_mcp_health(&change.name, &status, change.result.response_time_ms),
+        }
+        if let Some(app) = self.app.get() {
+            let _ = app.emit("mcp-status-changed", &change);
+        }
+    }
+
+    /// Servers whose interval has elapsed; forgets servers no longer configured or enabled
+    fn due(&self, servers: &BTreeMap<String, McpServerConfig>, now: Instant) -> Vec<String> {
+        let mut known = self.known.lock().unwrap();
+        known.retain(|name, _| servers.get(name).is_some_and(|s| s.enabled));
+        servers
+            .iter()
+            .filter(|(name, server)| server.enabled && known.get(*name).map_or(true, |k| k.next_due <= now))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Record Ollama and Claude CLI availability in the history, remediating Ollama if it is down
+async fn sample_services(app: AppHandle, monitor: Arc<HealthMonitor>, ollama_url: String, retention_days: u32) {
+    let start = Instant::now();
+    let ollama = match check_ollama_running(&ollama_url).await {
+        Ok(true) => HealthSample::now(McpStatus::Online, Some(start.elapsed().as_millis() as u64)),
+        Ok(false) | Err(_) => HealthSample::now(McpStatus::Offline, None),
+    };
+    monitor.history.append(OLLAMA_TARGET, &ollama, retention_days);
+    remediate(&app, OLLAMA_TARGET, ollama.status == McpStatus::Online).await;
+
+    let claude = if check_claude_installed().await { McpStatus::Online } else { McpStatus::Offline };
+    monitor.history.append(CLAUDE_TARGET, &HealthSample::now(claude, None), retention_days);
+}
+
+/// Run the monitor for the lifetime of the app, re-reading the config every tick
+pub fn spawn(app: AppHandle) {
+    let monitor = app.state::<AppState>().monitor.clone();
+    let _ = monitor.app.set(app.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(TICK);
+        let mut running: BTreeMap<String, tokio::task::JoinHandle<()>> = BTreeMap::new();
+        let mut services_due = Instant::now();
+        loop {
+            ticker.tick().await;
+            running.retain(|_, task| !task.is_finished());
+
+            let config = app.state::<AppState>().config.current();
+            let retention_days = config.launcher.health_history_days;
+            if services_due <= Instant::now() {
+                services_due = Instant::now() + SERVICE_INTERVAL;
+                tokio::spawn(sample_services(app.clone(), monitor.clone(), config.ollama_url(), retention_days));
+            }
+
+            let due: Vec<String> = monitor
+                .due(&config.mcp_tools, Instant::now())
+                .into_iter()
+                .filter(|name| !running.contains_key(name))
+                .collect();
+            if due.is_empty() {
+                continue;
+            }
+
+            for name in due {
+                let server = config.mcp_tools[&name].clone();
+                let state = app.state::<AppState>();
+                let (info, channel) = (state.mcp.info(&name), state.mcp.channel(&name));
+                let (app, monitor, task_name) = (app.clone(), monitor.clone(), name.clone());
+                let task = tokio::spawn(async move {
+                    let result = check_supervised(&task_name, &server, &info, channel).await;
+                    let stopped = result.status == McpStatus::Stopped;
+                    let up = matches!(result.status, McpStatus::Online | McpStatus::Degraded);
+                    monitor.record(&server, result, retention_days);
+                    // Runs inside the check task, so the next check waits for the action to finish.
+                    // A stdio server nobody started isn't down.
+                    if !stopped {
+                        remediate(&app, &task_name, up).await;
+                    }
+                });
+                running.insert(name, task);
+            }
+        }
+    });
+}
@@ -0,0 +1,370 @@
+use crate::config::McpServerConfig;
+use crate::process::registry::{registry, ChildKind};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::net::TcpListener;
+use std::sync::{Mutex, OnceLock};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// How far above a taken port to look for a free one
+const FREE_PORT_SEARCH: u16 = 200;
+
+/// Words too common in command lines to tell one server from another
+const GENERIC_WORDS: &[&str] = &[
+    "bunx", "cmd", "com", "from", "git", "github", "http", "https", "latest", "mcp", "node", "npm", "npx", "pnpm",
+    "python", "python3", "server", "start", "stdio", "uvx",
+];
+
+/// Process listening on a port
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub name: String,
+    pub cmd: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PortOwnership {
+    /// Nothing is listening
+    Free,
+    /// The server itself (or a process it spawned) is listening
+    Expected,
+    /// An unrelated process holds the port
+    Foreign,
+    /// Something listens but its PID can't be read (e.g. another user's process)
+    Unknown,
+}
+
+/// Ownership of one configured MCP port, from `check_mcp_ports`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortCheck {
+    pub server: String,
+    pub port: u16,
+    pub ownership: PortOwnership,
+    pub owner: Option<PortOwner>,
+    pub message: Option<String>,
+}
+
+/// Result of `reassign_mcp_port`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortReassignment {
+    pub server: String,
+    pub old_port: u16,
+    pub new_port: u16,
+    /// The server is running on the old port and must be restarted to pick up the change
+    pub restart_required: bool,
+}
+
+/// Every listening TCP socket as `(port, pid)`, from `/proc/net/tcp*` and the sockets in `/proc/<pid>/fd`
+#[cfg(target_os = "linux")]
+pub fn listeners() -> Vec<(u16, u32)> {
+    use std::fs;
+
+    const TCP_LISTEN: &str = "0A";
+    let mut inodes: BTreeMap<String, u16> = BTreeMap::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(content) = fs::read_to_string(table) else {
+            continue;
+        };
+        for fields in content.lines().skip(1).map(|l| l.split_whitespace().collect::<Vec<_>>()) {
+            let port = fields.get(1).and_then(|a| a.rsplit(':').next()).and_then(|p| u16::from_str_radix(p, 16).ok());
+            if let (Some(port), Some(&TCP_LISTEN)) = (port, fields.get(3)) {
+                if let Some(inode) = fields.get(9).filter(|i| **i != "0") {
+                    inodes.insert(format!("socket:[{}]", inode), port);
+                }
+            }
+        }
+    }
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for link in fds.flatten().filter_map(|fd| fs::read_link(fd.path()).ok()) {
+            if let Some(port) = inodes.get(link.to_string_lossy().as_ref()) {
+                found.push((*port, pid));
+            }
+        }
+    }
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+/// Every listening TCP socket as `(port, pid)`, from `lsof`
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn listeners() -> Vec<(u16, u32)> {
+    let Ok(output) = std::process::Command::new("lsof").args(["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pn"]).output() else {
+        return Vec::new();
+    };
+    // `p<pid>` starts a process block, `n<addr>:<port>` lists its sockets
+    let mut found = Vec::new();
+    let mut pid = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(p) = line.strip_prefix('p') {
+            pid = p.parse().ok();
+        } else if let (Some(addr), Some(pid)) = (line.strip_prefix('n'), pid) {
+            if let Some(port) = addr.rsplit(':').next().and_then(|p| p.parse().ok()) {
+                found.push((port, pid));
+            }
+        }
+    }
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+/// Every listening TCP socket as `(port, pid)`, from `netstat -ano`
+#[cfg(windows)]
+pub fn listeners() -> Vec<(u16, u32)> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let Ok(output) = std::process::Command::new("netstat").args(["-ano"]).creation_flags(CREATE_NO_WINDOW).output() else {
+        return Vec::new();
+    };
+    let mut found: Vec<(u16, u32)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .filter(|f| f.len() == 5 && f[0] == "TCP" && f[3] == "LISTENING")
+        .filter_map(|f| Some((f[1].rsplit(':').next()?.parse().ok()?, f[4].parse().ok()?)))
+        .collect();
+    found.sort_unstable();
+    found.dedup();
+    found
+}
+
+/// Listening sockets and the processes behind them, read once for a batch of port checks
+struct Snapshot {
+    listening: Vec<(u16, u32)>,
+    sys: System,
+}
+
+impl Snapshot {
+    /// The process table is only read when something listens on one of `ports`
+    fn take(ports: &[u16]) -> Self {
+        let listening = listeners();
+        let mut sys = System::new();
+        if listening.iter().any(|(port, _)| ports.contains(port)) {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+            );
+        }
+        Self { listening, sys }
+    }
+
+    fn listening_pids(&self, port: u16) -> Vec<u32> {
+        self.listening.iter().filter(|(p, _)| *p == port).map(|(_, pid)| *pid).collect()
+    }
+}
+
+/// Words from the server's name, command and arguments that should appear in its process
+fn identifying_words(name: &str, server: &McpServerConfig) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let args = server.args.iter().filter(|a| !a.starts_with('-')).map(String::as_str);
+    for part in [name, server.command.as_str()].into_iter().chain(args) {
+        words.extend(
+            part.to_lowercase()
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+                .filter(|w| w.len() >= 4 && !GENERIC_WORDS.contains(w) && w.parse::<u32>().is_err())
+                .map(String::from),
+        );
+    }
+    words.dedup();
+    words
+}
+
+/// Whether `owner` is the server: a descendant of its supervised process, or a process
+/// whose command line names it when it was started outside the launcher
+fn owned_by_server(sys: &System, owner: &PortOwner, name: &str, server: &McpServerConfig) -> bool {
+    let supervised: Vec<u32> = registry()
+        .children()
+        .into_iter()
+        .filter(|c| c.kind == ChildKind::McpServer && c.name == name)
+        .map(|c| c.pid)
+        .collect();
+    let mut pid = Some(Pid::from_u32(owner.pid));
+    for _ in 0..32 {
+        let Some(current) = pid else {
+            break;
+        };
+        if supervised.contains(&current.as_u32()) {
+            return true;
+        }
+        pid = sys.process(current).and_then(|p| p.parent());
+    }
+
+    let cmd = format!("{} {}", owner.name, owner.cmd.join(" ")).to_lowercase();
+    identifying_words(name, server).iter().any(|w| cmd.contains(w.as_str()))
+}
+
+fn inspect_port(snapshot: &Snapshot, name: &str, server: &McpServerConfig, port: u16) -> PortCheck {
+    let pids = snapshot.listening_pids(port);
+    let check = |ownership, owner, message| PortCheck { server: name.to_string(), port, ownership, owner, message };
+
+    if pids.is_empty() {
+        // Listening sockets of other users' processes can be invisible; a bind attempt tells
+        return match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(_) => check(PortOwnership::Free, None, None),
+            Err(_) => check(PortOwnership::Unknown, None, Some(format!("Port {} is in use by an unidentified process", port))),
+        };
+    }
+
+    let sys = &snapshot.sys;
+    let owners: Vec<PortOwner> = pids
+        .iter()
+        .filter_map(|pid| {
+            let p = sys.process(Pid::from_u32(*pid))?;
+            Some(PortOwner {
+                pid: *pid,
+                name: p.name().to_string_lossy().into_owned(),
+                cmd: p.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect(),
+            })
+        })
+        .collect();
+
+    match owners.iter().find(|o| !owned_by_server(sys, o, name, server)) {
+        Some(foreign) => check(
+            PortOwnership::Foreign,
+            Some(foreign.clone()),
+            Some(format!("Port {} is held by {} (PID {}), not {}", port, foreign.name, foreign.pid, name)),
+        ),
+        None => check(PortOwnership::Expected, owners.into_iter().next(), None),
+    }
+}
+
+/// Who listens on the server's `health_port`; `None` without one
+pub async fn check_port_owner(name: &str, server: &McpServerConfig) -> Option<PortCheck> {
+    let port = server.health_port?;
+    let (name, server) = (name.to_string(), server.clone());
+    let check = tokio::task::spawn_blocking(move || inspect_port(&Snapshot::take(&[port]), &name, &server, port))
+        .await
+        .ok()?;
+    remember_owner(&check);
+    Some(check)
+}
+
+/// Port ownership of every enabled server with a `health_port`, from one snapshot
+pub async fn check_all_ports(servers: &BTreeMap<String, McpServerConfig>) -> Vec<PortCheck> {
+    let servers: Vec<(String, McpServerConfig, u16)> = servers
+        .iter()
+        .filter(|(_, s)| s.enabled)
+        .filter_map(|(name, s)| Some((name.clone(), s.clone(), s.health_port?)))
+        .collect();
+    let checks = tokio::task::spawn_blocking(move || {
+        let snapshot = Snapshot::take(&servers.iter().map(|(_, _, port)| *port).collect::<Vec<_>>());
+        servers.iter().map(|(name, server, port)| inspect_port(&snapshot, name, server, *port)).collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    checks.iter().for_each(remember_owner);
+    checks
+}
+
+/// Last ownership seen per server, so health checks only look again when the port changes hands
+fn owners() -> &'static Mutex<BTreeMap<String, PortCheck>> {
+    static OWNERS: OnceLock<Mutex<BTreeMap<String, PortCheck>>> = OnceLock::new();
+    OWNERS.get_or_init(Default::default)
+}
+
+fn remember_owner(check: &PortCheck) {
+    owners().lock().unwrap().insert(check.server.clone(), check.clone());
+}
+
+/// Ownership of an answering port: the last result while it keeps answering, a fresh check
+/// after it was down or while someone else holds it
+pub async fn known_port_owner(name: &str, server: &McpServerConfig) -> Option<PortCheck> {
+    let cached = owners().lock().unwrap().get(name).cloned();
+    match cached {
+        Some(check)
+            if Some(check.port) == server.health_port
+                && matches!(check.ownership, PortOwnership::Expected | PortOwnership::Unknown) =>
+        {
+            Some(check)
+        }
+        _ => check_port_owner(name, server).await,
+    }
+}
+
+/// Drop the remembered owner once the server's port stops answering
+pub fn forget_port_owner(name: &str) {
+    owners().lock().unwrap().remove(name);
+}
+
+/// First port above `from` that binds and no other configured server claims
+pub fn find_free_port(servers: &BTreeMap<String, McpServerConfig>, from: u16) -> Option<u16> {
+    let claimed: Vec<u16> = servers.values().filter_map(|s| s.health_port).collect();
+    (from.saturating_add(1)..=from.saturating_add(FREE_PORT_SEARCH))
+        .filter(|p| !claimed.contains(p))
+        .find(|p| TcpListener::bind(("127.0.0.1", *p)).is_ok())
+}
+
+/// Merge patch moving `name` from `old` to `new`: `health_port`, `health_url` and any
+/// argument that is the port or ends in `=<port>` / `:<port>`
+pub fn port_patch(name: &str, server: &McpServerConfig, old: u16, new: u16) -> Value {
+    let (old_s, new_s) = (old.to_string(), new.to_string());
+    let args: Vec<String> = server
+        .args
+        .iter()
+        .map(|arg| {
+            if *arg == old_s {
+                return new_s.clone();
+            }
+            for sep in ['=', ':'] {
+                if let Some(prefix) = arg.strip_suffix(&format!("{}{}", sep, old_s)) {
+                    return format!("{}{}{}", prefix, sep, new_s);
+                }
+            }
+            arg.clone()
+        })
+        .collect();
+
+    let mut entry = json!({ "health_port": new });
+    if args != server.args {
+        entry["args"] = json!(args);
+    }
+    if let Some(mut url) = server.health_url.as_deref().and_then(|u| reqwest::Url::parse(u).ok()) {
+        if url.port() == Some(old) && url.set_port(Some(new)).is_ok() {
+            entry["health_url"] = json!(url.to_string());
+        }
+    }
+    json!({ "mcp_tools": { name: entry } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HydraConfig;
+
+    #[test]
+    fn test_port_patch_and_identifying_words() {
+        let config = HydraConfig::default();
+        let server = McpServerConfig {
+            args: vec!["--port".into(), "9000".into(), "--listen=0.0.0.0:9000".into(), "@playwright/mcp@latest".into()],
+            health_url: Some("http://localhost:9000/sse".into()),
+            ..config.mcp_tools.values().next().unwrap().clone()
+        };
+
+        let patch = port_patch("playwright", &server, 9000, 9001);
+        assert_eq!(
+            patch,
+            json!({ "mcp_tools": { "playwright": {
+                "health_port": 9001,
+                "args": ["--port", "9001", "--listen=0.0.0.0:9001", "@playwright/mcp@latest"],
+                "health_url": "http://localhost:9001/sse",
+            }}})
+        );
+        assert_eq!(identifying_words("pw", &server), vec!["playwright"]);
+    }
+}
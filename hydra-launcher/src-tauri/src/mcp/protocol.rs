@@ -0,0 +1,531 @@
+use crate::config::{McpServerConfig, McpTransport};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{Client, Response, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::ChildStdin;
+use tokio::sync::oneshot;
+
+/// Protocol revision offered in `initialize`; servers answer with the one they speak
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+/// JSON-RPC "method not found", the answer to server requests the launcher doesn't handle
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// What a server reported during the `initialize` handshake
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpHandshake {
+    pub transport: McpTransport,
+    pub protocol_version: String,
+    pub server_name: String,
+    pub server_version: Option<String>,
+    pub capabilities: Value,
+    /// Round trip of the `ping` sent after initialization
+    pub ping_ms: u64,
+}
+
+/// Why a request to an MCP server failed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum McpRequestError {
+    /// The server answered with a JSON-RPC error
+    #[error("MCP error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("MCP request `{method}` timed out after {seconds}s")]
+    Timeout { method: String, seconds: u64 },
+    /// The connection failed or the server sent something unusable
+    #[error("{0}")]
+    Transport(String),
+}
+
+impl From<String> for McpRequestError {
+    fn from(message: String) -> Self {
+        Self::Transport(message)
+    }
+}
+
+impl From<McpRequestError> for String {
+    fn from(error: McpRequestError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Perform `initialize` + `ping` over the server's transport, each within its timeout
+pub async fn probe(server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> Result<McpHandshake, String> {
+    let mut client = McpClient::connect(server, channel).await?;
+    let start = Instant::now();
+    let result = match client.request("ping", json!({})).await {
+        Ok(_) => client.handshake(start.elapsed().as_millis() as u64),
+        Err(e) => Err(e.into()),
+    };
+    client.close().await;
+    result
+}
+
+/// `health_url`, else the default path on `health_port`
+fn endpoint_url(server: &McpServerConfig, default_path: &str) -> Result<String, String> {
+    match (&server.health_url, server.health_port) {
+        (Some(url), _) => Ok(url.clone()),
+        (None, Some(port)) => Ok(format!("http://127.0.0.1:{}{}", port, default_path)),
+        (None, None) => Err("No health_url or health_port configured".to_string()),
+    }
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+/// The result of the response to request `id`, or `None` if `message` is something else
+fn match_response(message: &Value, id: u64) -> Option<Result<Value, McpRequestError>> {
+    if message.get("id").and_then(Value::as_u64) != Some(id) || message.get("method").is_some() {
+        return None;
+    }
+    Some(match message.get("error") {
+        Some(error) => Err(McpRequestError::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or_default(),
+            message: error.get("message").and_then(Value::as_str).unwrap_or("unknown error").to_string(),
+        }),
+        None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+    })
+}
+
+fn handshake(transport: McpTransport, init: &Value, ping_ms: u64) -> Result<McpHandshake, String> {
+    let protocol_version = init
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .ok_or("initialize result is missing protocolVersion")?;
+    let info = init.get("serverInfo");
+    Ok(McpHandshake {
+        transport,
+        protocol_version: protocol_version.to_string(),
+        server_name: info.and_then(|i| i.get("name")).and_then(Value::as_str).unwrap_or("unknown").to_string(),
+        server_version: info.and_then(|i| i.get("version")).and_then(Value::as_str).map(String::from),
+        capabilities: init.get("capabilities").cloned().unwrap_or_else(|| json!({})),
+        ping_ms,
+    })
+}
+
+/// An initialized client session with one MCP server
+pub struct McpClient {
+    conn: Connection,
+    transport: McpTransport,
+    /// Result of `initialize`
+    init: Value,
+    next_id: u64,
+    /// Limit for each request; `server.timeout_seconds`
+    timeout: Duration,
+}
+
+enum Connection {
+    /// The stdin/stdout of the server process the supervisor runs
+    Stdio { channel: Arc<StdioChannel> },
+    /// Legacy HTTP+SSE: responses arrive on the GET stream, requests go to the announced endpoint
+    Sse { client: Client, stream: Response, events: SseReader, post_url: Url },
+    /// Streamable HTTP: every message is a POST; responses come back as JSON or as a short SSE stream
+    Http { client: Client, url: String, session: Option<String> },
+}
+
+impl McpClient {
+    /// Connect over the server's transport and complete the `initialize` handshake.
+    /// Stdio servers are reached through `channel`, the supervised process; without one the
+    /// server isn't running and the connection fails rather than starting another copy.
+    pub async fn connect(server: &McpServerConfig, channel: Option<Arc<StdioChannel>>) -> Result<Self, String> {
+        let timeout = Duration::from_secs(server.timeout_seconds);
+        let transport = server.transport();
+        let conn = match transport {
+            McpTransport::Stdio => {
+                let channel = channel.ok_or("Server is not running")?;
+                // One MCP session per process: initialize once, then share it
+                let init = tokio::time::timeout(timeout, channel.initialize())
+                    .await
+                    .map_err(|_| format!("MCP request `initialize` timed out after {}s", server.timeout_seconds))??;
+                return Ok(Self { conn: Connection::Stdio { channel }, transport, init, next_id: 1, timeout });
+            }
+            McpTransport::Sse => {
+                let url = endpoint_url(server, "/sse")?;
+                tokio::time::timeout(timeout, connect_sse(&url))
+                    .await
+                    .map_err(|_| format!("MCP connection timed out after {}s", server.timeout_seconds))??
+            }
+            McpTransport::Http => Connection::Http { client: Client::new(), url: endpoint_url(server, "/mcp")?, session: None },
+        };
+
+        let mut client = Self { conn, transport, init: Value::Null, next_id: 1, timeout };
+        let initialized = match client.request("initialize", initialize_params()).await {
+            Ok(init) => {
+                client.init = init;
+                client.notify("notifications/initialized").await
+            }
+            Err(e) => Err(e.into()),
+        };
+        match initialized {
+            Ok(()) => Ok(client),
+            Err(e) => {
+                client.close().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// What the server reported in `initialize`
+    pub fn handshake(&self, ping_ms: u64) -> Result<McpHandshake, String> {
+        handshake(self.transport, &self.init, ping_ms)
+    }
+
+    /// `capabilities` the server declared in `initialize`
+    pub fn capabilities(&self) -> &Value {
+        self.init.get("capabilities").unwrap_or(&Value::Null)
+    }
+
+    /// Send a request and wait for its result within the client's timeout
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value, McpRequestError> {
+        self.request_within(method, params, self.timeout).await
+    }
+
+    /// Like `request`, with an explicit limit
+    pub async fn request_within(
+        &mut self,
+        method: &str,
+        params: Value,
+        limit: Duration,
+    ) -> Result<Value, McpRequestError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let exchange = async {
+            match &mut self.conn {
+                Connection::Stdio { channel } => channel.request(method, params).await,
+                Connection::Sse { client, stream, events, post_url } => {
+                    post(client, post_url, &request(id, method, params)).await?;
+                    events.response(stream, id).await
+                }
+                Connection::Http { client, url, session } => {
+                    let message = request(id, method, params);
+                    let (result, new_session) = http_exchange(client, url, session.as_deref(), &message, Some(id)).await?;
+                    *session = new_session;
+                    result.ok_or_else(|| "Server response did not answer the request".to_string().into())
+                }
+            }
+        };
+        match tokio::time::timeout(limit, exchange).await {
+            Ok(result) => result,
+            Err(_) => Err(McpRequestError::Timeout { method: method.to_string(), seconds: limit.as_secs() }),
+        }
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        let send = async {
+            match &mut self.conn {
+                Connection::Stdio { channel } => channel.send(&message).await,
+                Connection::Sse { client, post_url, .. } => post(client, post_url, &message).await,
+                Connection::Http { client, url, session } => {
+                    http_exchange(client, url, session.as_deref(), &message, None).await.map(|_| ()).map_err(String::from)
+                }
+            }
+        };
+        tokio::time::timeout(self.timeout, send)
+            .await
+            .unwrap_or_else(|_| Err(format!("MCP notification `{}` timed out", method)))
+    }
+
+    /// End the session. HTTP sessions are deleted so they don't pile up on the server;
+    /// the stdio session belongs to the supervised process and stays open.
+    pub async fn close(self) {
+        if let Connection::Http { client, url, session: Some(session) } = self.conn {
+            let _ = client.delete(&url).header(SESSION_HEADER, session).send().await;
+        }
+    }
+}
+
+fn initialize_params() -> Value {
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "hydra-launcher", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+// --- stdio ---
+
+type Reply = oneshot::Sender<Result<Value, McpRequestError>>;
+
+/// JSON-RPC over the stdin/stdout of a supervised stdio server, shared by every client of it.
+/// The supervisor feeds it each stdout line; lines that aren't meant for the launcher are
+/// logged as before.
+pub struct StdioChannel {
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: Mutex<HashMap<u64, Reply>>,
+    next_id: AtomicU64,
+    /// Result of `initialize`, once the session is set up
+    init: tokio::sync::Mutex<Option<Value>>,
+    closed: AtomicBool,
+}
+
+/// Drops the pending entry of a request that timed out or was cancelled
+struct PendingGuard<'a> {
+    channel: &'a StdioChannel,
+    id: u64,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.channel.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl StdioChannel {
+    pub fn new(stdin: ChildStdin) -> Self {
+        Self {
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            init: tokio::sync::Mutex::new(None),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Handle one stdout line; returns whether it was JSON-RPC traffic for the launcher.
+    /// Server requests get an answer (`ping`, else method not found); notifications and
+    /// anything that isn't JSON-RPC (banners, stray output) are left to the log.
+    pub async fn dispatch(&self, line: &str) -> bool {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return false;
+        };
+        let Some(id) = message.get("id").cloned() else {
+            return false;
+        };
+        if let Some(method) = message.get("method").and_then(Value::as_str) {
+            let reply = match method {
+                "ping" => json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+                _ => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": METHOD_NOT_FOUND, "message": format!("Method not found: {}", method) },
+                }),
+            };
+            let _ = self.send(&reply).await;
+            return true;
+        }
+
+        let Some(id) = id.as_u64() else {
+            return false;
+        };
+        let Some(reply) = self.pending.lock().unwrap().remove(&id) else {
+            return false;
+        };
+        if let Some(response) = match_response(&message, id) {
+            let _ = reply.send(response);
+        }
+        true
+    }
+
+    pub async fn send(&self, message: &Value) -> Result<(), String> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err("Server exited".to_string());
+        }
+        let line = format!("{}\n", message);
+        let mut stdin = self.stdin.lock().await;
+        stdin.write_all(line.as_bytes()).await.map_err(|e| format!("Failed to write to server: {}", e))?;
+        stdin.flush().await.map_err(|e| format!("Failed to write to server: {}", e))
+    }
+
+    /// Send a request and wait for its response; the caller applies the timeout
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, McpRequestError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let _guard = PendingGuard { channel: self, id };
+
+        self.send(&request(id, method, params)).await?;
+        rx.await.unwrap_or_else(|_| Err("Server exited before answering".to_string().into()))
+    }
+
+    /// `initialize` result of the process, running the handshake on first use
+    async fn initialize(&self) -> Result<Value, McpRequestError> {
+        let mut init = self.init.lock().await;
+        if let Some(init) = init.as_ref() {
+            return Ok(init.clone());
+        }
+        let result = self.request("initialize", initialize_params()).await?;
+        self.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await?;
+        *init = Some(result.clone());
+        Ok(result)
+    }
+
+    /// The process exited: fail pending and future requests
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+// --- HTTP transports ---
+
+#[derive(Debug, Default)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental `text/event-stream` parser over a streaming response
+#[derive(Default)]
+struct SseReader {
+    buf: String,
+}
+
+impl SseReader {
+    async fn next(&mut self, response: &mut Response) -> Result<SseEvent, String> {
+        loop {
+            if let Some(end) = self.buf.find("\n\n") {
+                let block: String = self.buf.drain(..end + 2).collect();
+                let mut event = SseEvent { event: "message".to_string(), ..Default::default() };
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event.event = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        if !event.data.is_empty() {
+                            event.data.push('\n');
+                        }
+                        event.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+                    }
+                }
+                if !event.data.is_empty() {
+                    return Ok(event);
+                }
+                continue;
+            }
+
+            match response.chunk().await.map_err(|e| format!("Event stream failed: {}", e))? {
+                Some(chunk) => self.buf.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n")),
+                None => return Err("Event stream closed before a response arrived".to_string()),
+            }
+        }
+    }
+
+    /// Skip events until the JSON-RPC response to `id`
+    async fn response(&mut self, response: &mut Response, id: u64) -> Result<Value, McpRequestError> {
+        loop {
+            let event = self.next(response).await?;
+            if event.event != "message" {
+                continue;
+            }
+            if let Some(result) = serde_json::from_str(&event.data).ok().and_then(|m: Value| match_response(&m, id)) {
+                return result;
+            }
+        }
+    }
+}
+
+fn http_error(e: reqwest::Error) -> String {
+    format!("Connection failed: {}", e)
+}
+
+async fn connect_sse(url: &str) -> Result<Connection, String> {
+    let client = Client::new();
+    let mut stream = client
+        .get(url)
+        .header(ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .and_then(Response::error_for_status)
+        .map_err(http_error)?;
+    let mut events = SseReader::default();
+
+    let endpoint = loop {
+        let event = events.next(&mut stream).await?;
+        if event.event == "endpoint" {
+            break event.data;
+        }
+    };
+    let base = Url::parse(url).map_err(|e| format!("Invalid health_url {}: {}", url, e))?;
+    let post_url = base.join(endpoint.trim()).map_err(|e| format!("Invalid endpoint {}: {}", endpoint, e))?;
+    Ok(Connection::Sse { client, stream, events, post_url })
+}
+
+async fn post(client: &Client, url: &Url, message: &Value) -> Result<(), String> {
+    client.post(url.clone()).json(message).send().await.and_then(Response::error_for_status).map_err(http_error)?;
+    Ok(())
+}
+
+async fn http_exchange(
+    client: &Client,
+    url: &str,
+    session: Option<&str>,
+    message: &Value,
+    id: Option<u64>,
+) -> Result<(Option<Value>, Option<String>), McpRequestError> {
+    let mut request = client
+        .post(url)
+        .header(ACCEPT, "application/json, text/event-stream")
+        .json(message);
+    if let Some(session) = session {
+        request = request.header(SESSION_HEADER, session);
+    }
+    let mut response = request.send().await.and_then(Response::error_for_status).map_err(http_error)?;
+
+    let session = response
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .or_else(|| session.map(String::from));
+    let Some(id) = id else {
+        return Ok((None, session));
+    };
+
+    let is_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    let result = if is_stream {
+        SseReader::default().response(&mut response, id).await?
+    } else {
+        let body: Value = response.json().await.map_err(|e| format!("Invalid JSON-RPC response: {}", e))?;
+        // A batch response is an array
+        let messages = match body {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        messages
+            .iter()
+            .find_map(|m| match_response(m, id))
+            .unwrap_or_else(|| Err("Server response did not answer the request".to_string().into()))?
+    };
+    Ok((Some(result), session))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_response_and_handshake() {
+        let init = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "protocolVersion": "2024-11-05",
+                "serverInfo": {"name": "desktop-commander", "version": "0.2.3"},
+                "capabilities": {"tools": {}}
+            }
+        });
+        assert!(match_response(&init, 2).is_none());
+        assert!(match_response(&json!({"jsonrpc": "2.0", "id": 1, "method": "roots/list"}), 1).is_none());
+
+        let result = match_response(&init, 1).unwrap().unwrap();
+        let handshake = handshake(McpTransport::Stdio, &result, 3).unwrap();
+        assert_eq!(handshake.server_name, "desktop-commander");
+        assert_eq!(handshake.server_version.as_deref(), Some("0.2.3"));
+        assert_eq!(handshake.capabilities, json!({"tools": {}}));
+
+        let error = json!({"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "Method not found"}});
+        assert_eq!(match_response(&error, 2).unwrap().unwrap_err().to_string(), "MCP error -32601: Method not found");
+    }
+}
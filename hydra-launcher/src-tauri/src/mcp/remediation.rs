@@ -0,0 +1,227 @@
+use super::history::OLLAMA_TARGET;
+use crate::commands::{start_configured_mcp, AppState};
+use crate::config::{RemediationAction, RemediationRule};
+use crate::logger::{log_error, log_info, log_warn};
+use crate::process::ollama::{check_ollama_running, ollama_serve_running, start_ollama};
+use crate::process::registry::{registry, ChildKind};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemediationOutcome {
+    Succeeded,
+    Failed,
+    Notified,
+    /// `max_attempts` reached; no further actions until the service recovers
+    GaveUp,
+}
+
+/// Payload of `mcp-remediation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationEvent {
+    /// MCP server name or `ollama`
+    pub target: String,
+    pub action: RemediationAction,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub outcome: RemediationOutcome,
+    pub message: Option<String>,
+    pub timestamp: String,
+}
+
+/// Rule state of one target, from `get_remediation_status`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemediationStatus {
+    pub target: String,
+    /// Consecutive failed checks since the last action
+    pub failures: u32,
+    /// Actions taken since the target was last up
+    pub attempts: u32,
+    pub gave_up: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Decision {
+    Act(u32),
+    GiveUp,
+}
+
+#[derive(Default)]
+struct RuleState {
+    failures: u32,
+    attempts: u32,
+    last_action: Option<Instant>,
+    gave_up: bool,
+}
+
+/// Counts failed checks per target and decides when a rule fires
+#[derive(Default)]
+pub struct Remediator {
+    state: Mutex<BTreeMap<String, RuleState>>,
+}
+
+impl Remediator {
+    /// Feed one check result; recovery resets the target, including a given-up rule
+    fn observe(&self, target: &str, up: bool, rule: &RemediationRule, now: Instant) -> Option<Decision> {
+        let mut states = self.state.lock().unwrap();
+        if up {
+            if let Some(state) = states.remove(target).filter(|s| s.attempts > 0) {
+                log_info(&format!("Remediation: {} recovered after {} attempt(s)", target, state.attempts));
+            }
+            return None;
+        }
+
+        let state = states.entry(target.to_string()).or_default();
+        state.failures += 1;
+        if state.gave_up || state.failures < rule.after_failures.max(1) {
+            return None;
+        }
+        let cooldown = Duration::from_secs(rule.cooldown_seconds);
+        if state.last_action.is_some_and(|t| now.duration_since(t) < cooldown) {
+            return None;
+        }
+        if state.attempts >= rule.max_attempts {
+            state.gave_up = true;
+            return Some(Decision::GiveUp);
+        }
+        state.attempts += 1;
+        state.failures = 0;
+        state.last_action = Some(now);
+        Some(Decision::Act(state.attempts))
+    }
+
+    /// Drop the state of a target that isn't supposed to be running
+    fn forget(&self, target: &str) {
+        self.state.lock().unwrap().remove(target);
+    }
+
+    /// Targets that failed at least once since they were last up
+    pub fn status(&self) -> Vec<RemediationStatus> {
+        self.state
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(target, s)| RemediationStatus {
+                target: target.clone(),
+                failures: s.failures,
+                attempts: s.attempts,
+                gave_up: s.gave_up,
+            })
+            .collect()
+    }
+}
+
+async fn run_action(state: &AppState, target: &str, action: RemediationAction) -> Result<(), String> {
+    match action {
+        RemediationAction::Restart => {
+            if target == OLLAMA_TARGET {
+                return Err("restart only applies to MCP servers".to_string());
+            }
+            if state.mcp.info(target).is_active() {
+                state.mcp.stop(target).await?;
+            }
+            start_configured_mcp(state, target).await.map(|_| ())
+        }
+        RemediationAction::StartOllama => {
+            // `ollama serve` can take longer than the cooldown to come up; don't stack another one
+            if registry().children().iter().any(|c| c.kind == ChildKind::Ollama) {
+                return Err("the `ollama serve` started earlier is still running".to_string());
+            }
+            let url = state.config.current().ollama_url();
+            if check_ollama_running(&url).await? {
+                return Ok(());
+            }
+            // Started by the tray app or by hand, and not answering yet
+            if tokio::task::spawn_blocking(ollama_serve_running).await.unwrap_or(false) {
+                return Err("an `ollama serve` process is already running".to_string());
+            }
+            start_ollama(&url).await
+        }
+        RemediationAction::Notify => Ok(()),
+    }
+}
+
+/// Apply the configured rule for `target` after a health check; no-op without a rule
+/// and for servers the user stopped
+pub async fn remediate(app: &AppHandle, target: &str, up: bool) {
+    let state = app.state::<AppState>();
+    let Some(rule) = state.config.current().remediation.get(target).cloned() else {
+        return;
+    };
+    if target != OLLAMA_TARGET && state.mcp.info(target).stopped_by_user() {
+        state.remediation.forget(target);
+        return;
+    }
+    let Some(decision) = state.remediation.observe(target, up, &rule, Instant::now()) else {
+        return;
+    };
+
+    let event = |attempt, outcome, message: Option<String>| RemediationEvent {
+        target: target.to_string(),
+        action: rule.action,
+        attempt,
+        max_attempts: rule.max_attempts,
+        outcome,
+        message,
+        timestamp: Local::now().to_rfc3339(),
+    };
+    let event = match decision {
+        Decision::GiveUp => {
+            log_error(&format!(
+                "Remediation: giving up on {} after {} attempt(s); waiting for it to recover",
+                target, rule.max_attempts
+            ));
+            event(rule.max_attempts, RemediationOutcome::GaveUp, None)
+        }
+        Decision::Act(attempt) => {
+            log_warn(&format!(
+                "Remediation: {} is down, {:?} (attempt {}/{})",
+                target, rule.action, attempt, rule.max_attempts
+            ));
+            match run_action(&state, target, rule.action).await {
+                Ok(()) if rule.action == RemediationAction::Notify => event(attempt, RemediationOutcome::Notified, None),
+                Ok(()) => {
+                    log_info(&format!("Remediation: {:?} for {} succeeded", rule.action, target));
+                    event(attempt, RemediationOutcome::Succeeded, None)
+                }
+                Err(e) => {
+                    log_error(&format!("Remediation: {:?} for {} failed: {}", rule.action, target, e));
+                    event(attempt, RemediationOutcome::Failed, Some(e))
+                }
+            }
+        }
+    };
+    let _ = app.emit("mcp-remediation", &event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_rate_limits_and_gives_up() {
+        let rule = RemediationRule { action: RemediationAction::Notify, after_failures: 2, cooldown_seconds: 60, max_attempts: 2 };
+        let remediator = Remediator::default();
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+
+        assert_eq!(remediator.observe("db", false, &rule, at(0)), None);
+        assert_eq!(remediator.observe("db", false, &rule, at(30)), Some(Decision::Act(1)));
+        assert_eq!(remediator.observe("db", false, &rule, at(40)), None);
+        // Enough failures again, but still inside the cooldown
+        assert_eq!(remediator.observe("db", false, &rule, at(50)), None);
+        assert_eq!(remediator.observe("db", false, &rule, at(95)), Some(Decision::Act(2)));
+        assert_eq!(remediator.observe("db", false, &rule, at(160)), None);
+        assert_eq!(remediator.observe("db", false, &rule, at(170)), Some(Decision::GiveUp));
+        assert_eq!(remediator.observe("db", false, &rule, at(500)), None);
+        assert!(remediator.status()[0].gave_up);
+
+        assert_eq!(remediator.observe("db", true, &rule, at(510)), None);
+        assert!(remediator.status().is_empty());
+    }
+}
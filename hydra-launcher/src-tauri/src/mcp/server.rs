@@ -1,57 +1,32 @@
-use std::process::Command;
+use crate::config::McpServerConfig;
+use std::collections::BTreeMap;
 
-/// Start an MCP server (placeholder - actual implementation depends on MCP server setup)
-#[allow(dead_code)]
-pub fn start_mcp_server(name: &str, command: &str, args: &[String]) -> Result<u32, String> {
-    let mut cmd = Command::new(command);
-    cmd.args(args);
-
-    // On Windows, spawn without visible console
+/// Command line for an MCP server, with stdio piped and the config env applied.
+/// The server leads its own process group so shutdown can reach its grandchildren.
+pub fn build_command(server: &McpServerConfig, env: &BTreeMap<String, String>) -> tokio::process::Command {
+    // npx/uvx are batch shims on Windows and need cmd to resolve them
     #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
+    let mut cmd = {
         const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-    }
-
-    match cmd.spawn() {
-        Ok(child) => {
-            log::info!("Started MCP server '{}' with PID: {}", name, child.id());
-            Ok(child.id())
-        }
-        Err(e) => Err(format!("Failed to start '{}': {}", name, e)),
-    }
-}
-
-/// Stop an MCP server by PID
-#[allow(dead_code)]
-#[cfg(windows)]
-pub fn stop_mcp_server(pid: u32) -> Result<(), String> {
-    let output = Command::new("taskkill")
-        .args(["/PID", &pid.to_string(), "/F"])
-        .output()
-        .map_err(|e| format!("Failed to execute taskkill: {}", e))?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
-}
-
-#[allow(dead_code)]
-#[cfg(not(windows))]
-pub fn stop_mcp_server(pid: u32) -> Result<(), String> {
-    use std::os::unix::process::CommandExt;
-
-    let output = Command::new("kill")
-        .args(["-9", &pid.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute kill: {}", e))?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C")
+            .arg(&server.command)
+            .creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new(&server.command);
+        cmd.process_group(0);
+        cmd
+    };
+
+    cmd.args(&server.args)
+        .envs(env)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+    cmd
 }
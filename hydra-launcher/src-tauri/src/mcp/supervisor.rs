@@ -0,0 +1,410 @@
+use super::logs::{capture, LogStream, ServerLog};
+use super::protocol::StdioChannel;
+use super::server::build_command;
+use crate::config::{McpServerConfig, McpTransport, RestartConfig, RestartPolicy};
+use crate::logger::{log_error, log_info, log_warn};
+use crate::process::registry::{registry, ChildKind};
+use crate::process::shutdown::{terminate_tree, ShutdownReport};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+/// `last_exit` of a server stopped through `stop`
+const STOPPED_BY_USER: &str = "stopped by user";
+
+/// Lifecycle of a supervised MCP server
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessState {
+    Stopped,
+    Starting,
+    Running,
+    /// Exited; waiting out the backoff before the next restart
+    Backoff,
+    Stopping,
+    /// Exited and will not be restarted (policy or restart limit)
+    Failed,
+}
+
+/// Payload of `list_mcp_processes` and the `mcp-process-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpProcessInfo {
+    pub name: String,
+    pub state: ProcessState,
+    pub pid: Option<u32>,
+    /// Restarts since the last manual start
+    pub restarts: u32,
+    pub started_at: Option<String>,
+    pub last_exit: Option<String>,
+    /// Delay before the pending restart while in `backoff`
+    pub backoff_ms: Option<u64>,
+    /// PIDs reaped by the most recent stop
+    pub last_shutdown: Option<ShutdownReport>,
+}
+
+impl McpProcessInfo {
+    fn stopped(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            state: ProcessState::Stopped,
+            pid: None,
+            restarts: 0,
+            started_at: None,
+            last_exit: None,
+            backoff_ms: None,
+            last_shutdown: None,
+        }
+    }
+
+    /// Running, or about to be (re)started
+    pub fn is_active(&self) -> bool {
+        !matches!(self.state, ProcessState::Stopped | ProcessState::Failed)
+    }
+
+    /// Stopped through `stop`, as opposed to never started or exited on its own
+    pub fn stopped_by_user(&self) -> bool {
+        self.state == ProcessState::Stopped && self.last_exit.as_deref() == Some(STOPPED_BY_USER)
+    }
+}
+
+struct Managed {
+    info: McpProcessInfo,
+    stop: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<()>>,
+    /// JSON-RPC over the running stdio server's pipes
+    channel: Option<Arc<StdioChannel>>,
+}
+
+/// Owns the MCP server child processes and restarts them according to their policy
+#[derive(Default)]
+pub struct Supervisor {
+    app: OnceLock<AppHandle>,
+    processes: Mutex<BTreeMap<String, Managed>>,
+}
+
+impl Supervisor {
+    /// Route state transitions to the UI as `mcp-process-changed`
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.app.set(app);
+    }
+
+    /// Every configured server, with `stopped` for those never started
+    pub fn list(&self, servers: &BTreeMap<String, McpServerConfig>) -> Vec<McpProcessInfo> {
+        let processes = self.processes.lock().unwrap();
+        let mut list: Vec<McpProcessInfo> = servers
+            .keys()
+            .map(|name| {
+                processes
+                    .get(name)
+                    .map(|m| m.info.clone())
+                    .unwrap_or_else(|| McpProcessInfo::stopped(name))
+            })
+            .collect();
+        // Keep showing processes whose server was since removed from the config
+        list.extend(processes.iter().filter(|(n, _)| !servers.contains_key(*n)).map(|(_, m)| m.info.clone()));
+        list
+    }
+
+    pub fn info(&self, name: &str) -> McpProcessInfo {
+        self.processes
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|m| m.info.clone())
+            .unwrap_or_else(|| McpProcessInfo::stopped(name))
+    }
+
+    /// Connection to the stdio server while its process is running
+    pub fn channel(&self, name: &str) -> Option<Arc<StdioChannel>> {
+        self.processes.lock().unwrap().get(name).and_then(|m| m.channel.clone())
+    }
+
+    /// Attach or detach the stdio channel of the current process; a detached one fails its pending requests
+    fn set_channel(&self, name: &str, channel: Option<Arc<StdioChannel>>) {
+        let previous = match self.processes.lock().unwrap().get_mut(name) {
+            Some(managed) => std::mem::replace(&mut managed.channel, channel),
+            None => None,
+        };
+        if let Some(previous) = previous {
+            previous.close();
+        }
+    }
+
+    pub fn start(
+        self: &Arc<Self>,
+        name: &str,
+        server: McpServerConfig,
+        env: BTreeMap<String, String>,
+    ) -> Result<McpProcessInfo, String> {
+        let mut processes = self.processes.lock().unwrap();
+        if processes.get(name).is_some_and(|m| m.info.is_active()) {
+            return Err(format!("MCP server already running: {}", name));
+        }
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let info = McpProcessInfo { state: ProcessState::Starting, ..McpProcessInfo::stopped(name) };
+        let task = tauri::async_runtime::spawn(self.clone().run(name.to_string(), server, env, stop_rx));
+        processes.insert(name.to_string(), Managed { info: info.clone(), stop: Some(stop_tx), task: Some(task), channel: None });
+        drop(processes);
+
+        self.emit(&info);
+        Ok(info)
+    }
+
+    pub async fn stop(&self, name: &str) -> Result<McpProcessInfo, String> {
+        let (stop, task) = {
+            let mut processes = self.processes.lock().unwrap();
+            let managed = processes
+                .get_mut(name)
+                .filter(|m| m.task.is_some())
+                .ok_or_else(|| format!("MCP server not running: {}", name))?;
+            (managed.stop.take(), managed.task.take())
+        };
+
+        self.update(name, |info| info.state = ProcessState::Stopping);
+        if let Some(stop) = stop {
+            let _ = stop.send(());
+        }
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+        Ok(self.info(name))
+    }
+
+    /// Stop every running server; used when the launcher exits
+    pub async fn stop_all(&self) {
+        let names: Vec<String> = self
+            .processes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, m)| m.task.is_some())
+            .map(|(n, _)| n.clone())
+            .collect();
+        for name in names {
+            let _ = self.stop(&name).await;
+        }
+    }
+
+    fn update(&self, name: &str, f: impl FnOnce(&mut McpProcessInfo)) {
+        let info = {
+            let mut processes = self.processes.lock().unwrap();
+            let Some(managed) = processes.get_mut(name) else {
+                return;
+            };
+            f(&mut managed.info);
+            managed.info.clone()
+        };
+        self.emit(&info);
+    }
+
+    fn emit(&self, info: &McpProcessInfo) {
+        if let Some(app) = self.app.get() {
+            let _ = app.emit("mcp-process-changed", info);
+        }
+    }
+
+    /// Spawn, wait, and restart until stopped or the policy gives up
+    async fn run(
+        self: Arc<Self>,
+        name: String,
+        server: McpServerConfig,
+        env: BTreeMap<String, String>,
+        mut stop: oneshot::Receiver<()>,
+    ) {
+        let policy = &server.restart;
+        let mut recent: VecDeque<Instant> = VecDeque::new();
+        let log = Arc::new(Mutex::new(ServerLog::open(&name)));
+
+        loop {
+            // stdin stays open so stdio servers don't exit on EOF; for stdio servers it
+            // carries the launcher's own MCP session (health checks, inventory, tool calls)
+            let mut command = build_command(&server, &env);
+            command.stderr(std::process::Stdio::piped());
+            let exit = match command.spawn() {
+                Ok(mut child) => {
+                    let pid = child.id();
+                    if let Some(pid) = pid {
+                        registry().register(pid, ChildKind::McpServer, &name);
+                    }
+                    let channel = match server.transport() {
+                        McpTransport::Stdio => child.stdin.take().map(|stdin| Arc::new(StdioChannel::new(stdin))),
+                        _ => None,
+                    };
+                    self.set_channel(&name, channel.clone());
+                    if let Some(stdout) = child.stdout.take() {
+                        let app = self.app.get().cloned();
+                        let task = capture(app, log.clone(), name.clone(), LogStream::Stdout, stdout, channel);
+                        tauri::async_runtime::spawn(task);
+                    }
+                    if let Some(stderr) = child.stderr.take() {
+                        let app = self.app.get().cloned();
+                        tauri::async_runtime::spawn(capture(app, log.clone(), name.clone(), LogStream::Stderr, stderr, None));
+                    }
+                    log_info(&format!("MCP server '{}' started with PID {}", name, pid.unwrap_or_default()));
+                    self.update(&name, |info| {
+                        info.state = ProcessState::Running;
+                        info.pid = pid;
+                        info.started_at = Some(Local::now().to_rfc3339());
+                        info.backoff_ms = None;
+                    });
+
+                    let exit = tokio::select! {
+                        status = child.wait() => match status {
+                            Ok(status) if status.success() => Ok(()),
+                            Ok(status) => Err(format!("exited with {}", status)),
+                            Err(e) => Err(format!("wait failed: {}", e)),
+                        },
+                        _ = &mut stop => {
+                            self.set_channel(&name, None);
+                            let grace = Duration::from_millis(server.shutdown_grace_ms);
+                            let report = match pid {
+                                Some(pid) => terminate_tree(pid, grace).await,
+                                None => ShutdownReport::default(),
+                            };
+                            // Collect the exit status; a no-op unless the leader outlived the group kill
+                            let _ = child.kill().await;
+                            log_shutdown(&name, &report);
+                            self.update(&name, |info| info.last_shutdown = Some(report));
+                            if let Some(pid) = pid {
+                                registry().unregister(pid);
+                            }
+                            self.finish(&name, ProcessState::Stopped, Some(STOPPED_BY_USER.to_string()));
+                            return;
+                        }
+                    };
+                    self.set_channel(&name, None);
+                    if let Some(pid) = pid {
+                        registry().unregister(pid);
+                    }
+                    exit
+                }
+                Err(e) => Err(format!("failed to start `{}`: {}", server.command, e)),
+            };
+
+            let description = match &exit {
+                Ok(()) => "exited cleanly".to_string(),
+                Err(e) => e.clone(),
+            };
+            let restart = match policy.policy {
+                RestartPolicy::Never => false,
+                RestartPolicy::OnFailure => exit.is_err(),
+                RestartPolicy::Always => true,
+            };
+            if !restart {
+                let state = if exit.is_ok() { ProcessState::Stopped } else { ProcessState::Failed };
+                log_warn(&format!("MCP server '{}' {}", name, description));
+                self.finish(&name, state, Some(description));
+                return;
+            }
+
+            let Some(delay) = next_backoff(policy, &mut recent, Instant::now()) else {
+                let reason = format!(
+                    "{}; gave up after {} restarts in {}s",
+                    description, policy.max_restarts, policy.window_seconds
+                );
+                log_error(&format!("MCP server '{}' {}", name, reason));
+                self.finish(&name, ProcessState::Failed, Some(reason));
+                return;
+            };
+
+            log_warn(&format!("MCP server '{}' {}, restarting in {}ms", name, description, delay.as_millis()));
+            self.update(&name, |info| {
+                info.state = ProcessState::Backoff;
+                info.pid = None;
+                info.last_exit = Some(description);
+                info.backoff_ms = Some(delay.as_millis() as u64);
+                info.restarts += 1;
+            });
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = &mut stop => {
+                    self.finish(&name, ProcessState::Stopped, Some(STOPPED_BY_USER.to_string()));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn finish(&self, name: &str, state: ProcessState, last_exit: Option<String>) {
+        if let Some(managed) = self.processes.lock().unwrap().get_mut(name) {
+            managed.stop = None;
+            managed.task = None;
+        }
+        self.update(name, |info| {
+            info.state = state;
+            info.pid = None;
+            info.backoff_ms = None;
+            if last_exit.is_some() {
+                info.last_exit = last_exit;
+            }
+        });
+    }
+}
+
+fn log_shutdown(name: &str, report: &ShutdownReport) {
+    let message = format!(
+        "MCP server '{}' stopped; reaped PIDs {:?}{}",
+        name,
+        report.reaped,
+        if report.killed.is_empty() { String::new() } else { format!(", killed after grace {:?}", report.killed) }
+    );
+    if report.remaining.is_empty() {
+        log_info(&message);
+    } else {
+        log_error(&format!("{}; still running {:?}", message, report.remaining));
+    }
+}
+
+/// Record a restart and return its delay, or `None` once the window's restart budget is spent
+fn next_backoff(policy: &RestartConfig, recent: &mut VecDeque<Instant>, now: Instant) -> Option<Duration> {
+    let window = Duration::from_secs(policy.window_seconds);
+    while recent.front().is_some_and(|t| now.duration_since(*t) > window) {
+        recent.pop_front();
+    }
+    if recent.len() >= policy.max_restarts as usize {
+        return None;
+    }
+
+    let exponent = recent.len().min(31) as u32;
+    let delay = policy.backoff_initial_ms.saturating_mul(1u64 << exponent).min(policy.backoff_max_ms);
+    recent.push_back(now);
+    Some(Duration::from_millis(delay))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_gives_up() {
+        let policy = RestartConfig {
+            backoff_initial_ms: 100,
+            backoff_max_ms: 300,
+            max_restarts: 3,
+            window_seconds: 60,
+            ..Default::default()
+        };
+        let mut recent = VecDeque::new();
+        let now = Instant::now();
+
+        let delays: Vec<_> = (0..4).map(|_| next_backoff(&policy, &mut recent, now)).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_millis(300)),
+                None
+            ]
+        );
+        // Restarts outside the window no longer count
+        assert!(next_backoff(&policy, &mut recent, now + Duration::from_secs(61)).is_some());
+    }
+}
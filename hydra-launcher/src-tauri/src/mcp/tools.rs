@@ -0,0 +1,333 @@
+use super::inventory::{self, InventoryCache, McpTool};
+use super::protocol::{McpClient, McpRequestError, StdioChannel};
+use crate::config::McpServerConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// One item of a tool result's `content`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum McpContent {
+    Text { text: String },
+    /// `data` is base64, as sent by the server
+    Image { data: String, mime_type: String },
+    Audio { data: String, mime_type: String },
+    ResourceLink { uri: String, name: Option<String>, description: Option<String>, mime_type: Option<String> },
+    /// Embedded resource; `blob` is base64
+    Resource { uri: String, mime_type: Option<String>, text: Option<String>, blob: Option<String> },
+    /// A content type this launcher doesn't know, passed through as sent
+    Other { value: Value },
+}
+
+/// Result of `call_mcp_tool`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpToolResult {
+    pub server: String,
+    pub tool: String,
+    pub content: Vec<McpContent>,
+    /// `structuredContent`, for tools with an output schema
+    pub structured_content: Option<Value>,
+    pub duration_ms: u64,
+}
+
+/// Why `call_mcp_tool` failed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum McpCallError {
+    #[error("Unknown MCP server: {server}")]
+    UnknownServer { server: String },
+    /// Disabled, or could not be started or reached
+    #[error("MCP server {server} is unavailable: {message}")]
+    Unavailable { server: String, message: String },
+    #[error("MCP server {server} has no tool {tool}")]
+    UnknownTool { server: String, tool: String },
+    /// `args` don't match the tool's input schema; one message per violation
+    #[error("Invalid arguments for {tool}: {}", errors.join("; "))]
+    InvalidArguments { tool: String, errors: Vec<String> },
+    #[error("{tool} timed out after {seconds}s")]
+    Timeout { tool: String, seconds: u64 },
+    /// The server rejected the request with a JSON-RPC error
+    #[error("MCP error {code}: {message}")]
+    Protocol { code: i64, message: String },
+    /// The tool ran and reported an error (`isError`)
+    #[error("{tool} failed: {message}")]
+    ToolFailed { tool: String, message: String, content: Vec<McpContent> },
+}
+
+fn text(item: &Value, key: &str) -> Option<String> {
+    item.get(key).and_then(Value::as_str).map(String::from)
+}
+
+fn parse_content(item: &Value) -> McpContent {
+    let kind = item.get("type").and_then(Value::as_str).unwrap_or_default();
+    let parsed = match kind {
+        "text" => text(item, "text").map(|text| McpContent::Text { text }),
+        "image" | "audio" => match (text(item, "data"), text(item, "mimeType")) {
+            (Some(data), Some(mime_type)) if kind == "image" => Some(McpContent::Image { data, mime_type }),
+            (Some(data), Some(mime_type)) => Some(McpContent::Audio { data, mime_type }),
+            _ => None,
+        },
+        "resource_link" => text(item, "uri").map(|uri| McpContent::ResourceLink {
+            uri,
+            name: text(item, "name"),
+            description: text(item, "description"),
+            mime_type: text(item, "mimeType"),
+        }),
+        "resource" => item.get("resource").and_then(|r| {
+            Some(McpContent::Resource {
+                uri: text(r, "uri")?,
+                mime_type: text(r, "mimeType"),
+                text: text(r, "text"),
+                blob: text(r, "blob"),
+            })
+        }),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| McpContent::Other { value: item.clone() })
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Check `value` against the subset of JSON Schema MCP tools use in practice: `type`, `enum`,
+/// `const`, `required`, `properties`, `additionalProperties`, `items`, numeric and length
+/// bounds, and `anyOf`/`oneOf`/`allOf`. `$ref` and formats are not checked.
+pub fn validate(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let at = if path.is_empty() { "arguments".to_string() } else { path.to_string() };
+    let mut fail = |message: String| errors.push(format!("{}: {}", at, message));
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+        fail(format!("expected {}, got {}", types.join(" or "), type_name(value)));
+        return;
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            fail(format!("must be one of {}", Value::Array(options.clone())));
+        }
+    }
+    if let Some(expected) = schema.get("const").filter(|c| *c != value) {
+        fail(format!("must be {}", expected));
+    }
+
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+    if let Some(n) = value.as_f64() {
+        if bound("minimum").is_some_and(|min| n < min) || bound("exclusiveMinimum").is_some_and(|min| n <= min) {
+            fail(format!("{} is below the minimum", n));
+        }
+        if bound("maximum").is_some_and(|max| n > max) || bound("exclusiveMaximum").is_some_and(|max| n >= max) {
+            fail(format!("{} is above the maximum", n));
+        }
+    }
+    if let Some(s) = value.as_str() {
+        let len = s.chars().count() as f64;
+        if bound("minLength").is_some_and(|min| len < min) || bound("maxLength").is_some_and(|max| len > max) {
+            fail(format!("length {} is out of bounds", len));
+        }
+    }
+    if let Some(items) = value.as_array() {
+        let len = items.len() as f64;
+        if bound("minItems").is_some_and(|min| len < min) || bound("maxItems").is_some_and(|max| len > max) {
+            fail(format!("{} items is out of bounds", len));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate(item_schema, item, &format!("{}[{}]", at, i), errors);
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        validate_object(schema, object, &at, errors);
+    }
+
+    for key in ["allOf", "anyOf", "oneOf"] {
+        let Some(branches) = schema.get(key).and_then(Value::as_array) else {
+            continue;
+        };
+        let results: Vec<Vec<String>> = branches
+            .iter()
+            .map(|branch| {
+                let mut branch_errors = Vec::new();
+                validate(branch, value, &at, &mut branch_errors);
+                branch_errors
+            })
+            .collect();
+        let matched = results.iter().filter(|e| e.is_empty()).count();
+        match key {
+            "allOf" => errors.extend(results.into_iter().flatten()),
+            "anyOf" if matched == 0 => errors.push(format!("{}: matches none of anyOf", at)),
+            "oneOf" if matched != 1 => errors.push(format!("{}: matches {} of oneOf, expected 1", at, matched)),
+            _ => {}
+        }
+    }
+}
+
+fn validate_object(schema: &Map<String, Value>, object: &Map<String, Value>, at: &str, errors: &mut Vec<String>) {
+    let child = |key: &str| if at == "arguments" { key.to_string() } else { format!("{}.{}", at, key) };
+    for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+        if !object.contains_key(key) {
+            errors.push(format!("{}: required", child(key)));
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, value) in object {
+        match (properties.and_then(|p| p.get(key)), schema.get("additionalProperties")) {
+            (Some(property), _) => validate(property, value, &child(key), errors),
+            (None, Some(Value::Bool(false))) => errors.push(format!("{}: not allowed", child(key))),
+            (None, Some(extra)) if extra.is_object() => validate(extra, value, &child(key), errors),
+            (None, _) => {}
+        }
+    }
+}
+
+fn check_args(tool: &McpTool, args: &Value) -> Result<(), McpCallError> {
+    let mut errors = Vec::new();
+    validate(&tool.input_schema, args, "", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(McpCallError::InvalidArguments { tool: tool.name.clone(), errors })
+    }
+}
+
+/// Validate `args` against the tool's input schema, call it and return its content, all within
+/// `limit`. The schema comes from a live `tools/list`; the inventory cache is refreshed when it
+/// no longer matches what the server offers.
+pub async fn call_tool(
+    cache: &InventoryCache,
+    name: &str,
+    server: &McpServerConfig,
+    channel: Option<Arc<StdioChannel>>,
+    tool: &str,
+    args: Value,
+    limit: Duration,
+) -> Result<McpToolResult, McpCallError> {
+    if !server.enabled {
+        return Err(McpCallError::Unavailable { server: name.to_string(), message: "disabled in config".to_string() });
+    }
+    // Null means no arguments
+    let args = if args.is_null() { json!({}) } else { args };
+    let unavailable = |message| McpCallError::Unavailable { server: name.to_string(), message };
+    let timed_out = || McpCallError::Timeout { tool: tool.to_string(), seconds: limit.as_secs() };
+    let begin = Instant::now();
+
+    let mut client = tokio::time::timeout(limit, McpClient::connect(server, channel))
+        .await
+        .map_err(|_| timed_out())?
+        .map_err(unavailable)?;
+    let call = async {
+        let tools = inventory::list_tools(&mut client).await.map_err(unavailable)?;
+        if cache.get(name).map_or(true, |inv| inv.tools != tools) {
+            cache.update_tools(name, server, tools.clone());
+        }
+        let listed = tools
+            .iter()
+            .find(|t| t.name == tool)
+            .ok_or_else(|| McpCallError::UnknownTool { server: name.to_string(), tool: tool.to_string() })?;
+        check_args(listed, &args)?;
+
+        let start = Instant::now();
+        let params = json!({ "name": tool, "arguments": args });
+        let remaining = limit.saturating_sub(begin.elapsed());
+        let response = client.request_within("tools/call", params, remaining).await.map_err(|e| match e {
+            McpRequestError::Timeout { .. } => timed_out(),
+            McpRequestError::Rpc { code, message } => McpCallError::Protocol { code, message },
+            McpRequestError::Transport(message) => unavailable(message),
+        })?;
+        Ok((response, start.elapsed().as_millis() as u64))
+    };
+    let result = tokio::time::timeout(limit.saturating_sub(begin.elapsed()), call)
+        .await
+        .unwrap_or_else(|_| Err(timed_out()));
+    client.close().await;
+    let (response, duration_ms) = result?;
+
+    let content: Vec<McpContent> =
+        response.get("content").and_then(Value::as_array).into_iter().flatten().map(parse_content).collect();
+    if response.get("isError").and_then(Value::as_bool).unwrap_or(false) {
+        let message = content
+            .iter()
+            .filter_map(|c| match c {
+                McpContent::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(McpCallError::ToolFailed { tool: tool.to_string(), message, content });
+    }
+    Ok(McpToolResult {
+        server: name.to_string(),
+        tool: tool.to_string(),
+        content,
+        structured_content: response.get("structuredContent").cloned(),
+        duration_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_parse_content() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name_path": { "type": "string", "minLength": 1 },
+                "depth": { "type": "integer", "minimum": 0 },
+                "kinds": { "type": "array", "items": { "enum": ["class", "function"] } }
+            },
+            "required": ["name_path"],
+            "additionalProperties": false
+        });
+        let mut errors = Vec::new();
+        validate(&schema, &json!({ "name_path": "Supervisor/start", "depth": 1, "kinds": ["class"] }), "", &mut errors);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        validate(&schema, &json!({ "depth": 1.5, "kinds": ["module"], "path": "." }), "", &mut errors);
+        assert_eq!(
+            errors,
+            vec![
+                "name_path: required",
+                "depth: expected integer, got number",
+                "kinds[0]: must be one of [\"class\",\"function\"]",
+                "path: not allowed",
+            ]
+        );
+
+        let image = parse_content(&json!({ "type": "image", "data": "iVBORw0=", "mimeType": "image/png" }));
+        assert_eq!(image, McpContent::Image { data: "iVBORw0=".into(), mime_type: "image/png".into() });
+        let link = parse_content(&json!({ "type": "resource_link", "uri": "file:///tmp/a.rs", "name": "a.rs" }));
+        assert!(matches!(link, McpContent::ResourceLink { name: Some(_), .. }));
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Application directory name under the platform base directories
+const APP_DIR: &str = "hydra";
+
+/// Legacy install directory under the user's Desktop
+const LEGACY_DIR: &str = "ClaudeHYDRA";
+
+/// Per-user HYDRA directories.
+///
+/// Linux follows XDG (`~/.config/hydra`, `~/.local/share/hydra`, `~/.local/state/hydra`,
+/// `~/.cache/hydra`); Windows uses the known folders (`%APPDATA%\hydra`,
+/// `%LOCALAPPDATA%\hydra`); macOS uses `~/Library/Application Support/hydra`.
+/// Each directory can be overridden with `HYDRA_<KIND>_DIR`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HydraPaths {
+    /// HYDRA install/project root (ai-handler, scripts); `HYDRA_PATH` or the legacy Desktop folder
+    pub root: Option<PathBuf>,
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub state_dir: PathBuf,
+}
+
+impl HydraPaths {
+    pub fn resolve() -> Self {
+        Self::from_env(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let base = |dir: Option<PathBuf>, fallback: &[&str]| {
+            dir.unwrap_or_else(|| fallback.iter().fold(home.clone(), |p, s| p.join(s))).join(APP_DIR)
+        };
+
+        let config_dir = var("HYDRA_CONFIG_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| base(dirs::config_dir(), &[".config"]));
+        let data_dir = var("HYDRA_DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| base(dirs::data_dir(), &[".local", "share"]));
+        // XDG state dir on Linux, local app data elsewhere
+        let state_dir = var("HYDRA_STATE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| base(dirs::state_dir().or_else(dirs::data_local_dir), &[".local", "state"]));
+        let log_dir = var("HYDRA_LOG_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| state_dir.join("logs"));
+        let cache_dir = var("HYDRA_CACHE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| base(dirs::cache_dir(), &[".cache"]));
+
+        let root = var("HYDRA_PATH")
+            .map(PathBuf::from)
+            .or_else(|| legacy_dir().filter(|d| d.is_dir()));
+
+        Self { root, config_dir, data_dir, log_dir, cache_dir, state_dir }
+    }
+
+    /// User-level hydra-config.json
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join("hydra-config.json")
+    }
+
+    /// Copy config and move logs out of the legacy `~/Desktop/ClaudeHYDRA` layout.
+    /// The legacy config is copied, not moved, because the PowerShell tooling still reads it.
+    /// Returns a description of each migrated item.
+    pub fn migrate_legacy(&self) -> Result<Vec<String>, String> {
+        let Some(legacy) = legacy_dir().filter(|d| d.is_dir()) else {
+            return Ok(Vec::new());
+        };
+        let mut migrated = Vec::new();
+
+        let legacy_config = legacy.join("hydra-config.json");
+        if legacy_config.is_file() && !self.config_file().exists() {
+            fs::create_dir_all(&self.config_dir).map_err(|e| format!("Failed to create {}: {}", self.config_dir.display(), e))?;
+            fs::copy(&legacy_config, self.config_file()).map_err(|e| format!("Failed to copy legacy config: {}", e))?;
+            migrated.push(format!("{} -> {}", legacy_config.display(), self.config_file().display()));
+        }
+
+        let legacy_logs = legacy.join("hydra-logs");
+        if legacy_logs.is_dir() && legacy_logs != self.log_dir {
+            let count = move_dir_contents(&legacy_logs, &self.log_dir)?;
+            if count > 0 {
+                migrated.push(format!("{} log files {} -> {}", count, legacy_logs.display(), self.log_dir.display()));
+                let _ = fs::remove_dir(&legacy_logs);
+            }
+        }
+
+        Ok(migrated)
+    }
+}
+
+/// `~/Desktop/ClaudeHYDRA`, the pre-10.5 location of everything
+pub fn legacy_dir() -> Option<PathBuf> {
+    dirs::desktop_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join("Desktop")))
+        .map(|d| d.join(LEGACY_DIR))
+}
+
+/// HYDRA install/project root, used as the working directory for Claude CLI
+pub fn hydra_root() -> Result<PathBuf, String> {
+    HydraPaths::resolve()
+        .root
+        .filter(|p| p.is_dir())
+        .ok_or_else(|| "HYDRA path not found. Set HYDRA_PATH environment variable.".to_string())
+}
+
+/// Move files from `from` into `to`, skipping names that already exist there
+fn move_dir_contents(from: &Path, to: &Path) -> Result<usize, String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+
+    let mut count = 0;
+    for entry in entries.filter_map(Result::ok).filter(|e| e.path().is_file()) {
+        let target = to.join(entry.file_name());
+        if target.exists() {
+            continue;
+        }
+        // rename fails across volumes, so fall back to copy + delete
+        if fs::rename(entry.path(), &target).is_err() {
+            fs::copy(entry.path(), &target).map_err(|e| format!("Failed to move {}: {}", entry.path().display(), e))?;
+            let _ = fs::remove_file(entry.path());
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_take_precedence() {
+        let paths = HydraPaths::from_env(|name| match name {
+            "HYDRA_PATH" => Some("/opt/hydra".to_string()),
+            "HYDRA_CONFIG_DIR" => Some("/etc/hydra".to_string()),
+            "HYDRA_STATE_DIR" => Some("/var/lib/hydra".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(paths.root, Some(PathBuf::from("/opt/hydra")));
+        assert_eq!(paths.config_file(), PathBuf::from("/etc/hydra").join("hydra-config.json"));
+        assert_eq!(paths.log_dir, PathBuf::from("/var/lib/hydra").join("logs"));
+        assert!(paths.cache_dir.ends_with(APP_DIR));
+    }
+}
@@ -1,7 +1,10 @@
+use super::registry::{track, ChildKind};
+use crate::paths::hydra_root;
+use std::collections::BTreeMap;
 use std::process::Command;
 
 /// Initial HYDRA prompt with instructions
-const HYDRA_INIT_PROMPT: &str = r#"HYDRA 10.4 ACTIVATED - Four-Headed Beast Mode
+const HYDRA_INIT_PROMPT: &str = r#"HYDRA 10.5 ACTIVATED - Four-Headed Beast Mode
 
 KONTEKST SESJI:
 - Dzialamy LOKALNIE na maszynie uzytkownika (Windows)
@@ -22,9 +25,9 @@ AKTYWNE NARZEDZIA MCP:
 
 Uruchom /hydra aby zobaczyc pelne instrukcje."#;
 
-/// Spawn Claude CLI with HYDRA configuration
-pub async fn spawn_claude_cli(yolo_mode: bool) -> Result<String, String> {
-    let hydra_path = get_hydra_path()?;
+/// Spawn Claude CLI with HYDRA configuration and the resolved config env
+pub async fn spawn_claude_cli(yolo_mode: bool, env: BTreeMap<String, String>) -> Result<String, String> {
+    let hydra_path = hydra_root()?;
 
     let mut args = vec![];
 
@@ -39,7 +42,7 @@ pub async fn spawn_claude_cli(yolo_mode: bool) -> Result<String, String> {
 
     log::info!("Launching Claude CLI with args: {:?}", args);
 
-    // Use start command on Windows to open in new terminal
+    // Open the CLI in a new terminal on Windows
     #[cfg(windows)]
     {
         // Build base args without prompt (no --cwd - it doesn't exist in Claude CLI)
@@ -54,52 +57,37 @@ pub async fn spawn_claude_cli(yolo_mode: bool) -> Result<String, String> {
 
         let claude_cmd = format!(
             "cd /d \"{}\" && claude {} -p \"{}\"",
-            hydra_path,
+            hydra_path.display(),
             base_args.join(" "),
             escaped_prompt
         );
 
-        Command::new("cmd")
-            .args(["/c", "start", "cmd", "/k", &claude_cmd])
+        // A console of its own instead of `start`, so the CLI stays our child
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+        let child = tokio::process::Command::new("cmd")
+            .args(["/k", &claude_cmd])
+            .envs(&env)
+            .creation_flags(CREATE_NEW_CONSOLE)
             .spawn()
             .map_err(|e| format!("Failed to launch Claude CLI: {}", e))?;
+        track(child, ChildKind::ClaudeCli, "claude");
     }
 
     #[cfg(not(windows))]
     {
-        Command::new("claude")
+        let child = tokio::process::Command::new("claude")
             .args(&args)
+            .envs(&env)
             .current_dir(&hydra_path)
             .spawn()
             .map_err(|e| format!("Failed to launch Claude CLI: {}", e))?;
+        track(child, ChildKind::ClaudeCli, "claude");
     }
 
     Ok("Claude CLI launched successfully".to_string())
 }
 
-/// Get the HYDRA project path
-fn get_hydra_path() -> Result<String, String> {
-    // Check environment variable first
-    if let Ok(path) = std::env::var("HYDRA_PATH") {
-        return Ok(path);
-    }
-
-    // Default to Desktop/ClaudeHYDRA
-    let home = std::env::var("USERPROFILE")
-        .or_else(|_| std::env::var("HOME"))
-        .map_err(|_| "Could not determine home directory")?;
-
-    let default_path = format!("{}\\Desktop\\ClaudeHYDRA", home);
-
-    if std::path::Path::new(&default_path).exists() {
-        Ok(default_path)
-    } else {
-        Err("HYDRA path not found. Set HYDRA_PATH environment variable.".to_string())
-    }
-}
-
 /// Check if Claude CLI is installed
-#[allow(dead_code)]
 pub async fn check_claude_installed() -> bool {
     #[cfg(windows)]
     {
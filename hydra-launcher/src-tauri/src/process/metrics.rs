@@ -0,0 +1,105 @@
+use super::registry::{registry, ChildKind};
+use super::shutdown::process_tree;
+use crate::mcp::ports::listeners;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often `process-metrics` is emitted
+const STREAM_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Resource usage of one launcher child and all of its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    pub name: String,
+    pub kind: ChildKind,
+    pub pid: u32,
+    /// Every PID in the tree, root first
+    pub pids: Vec<u32>,
+    /// Summed over the tree; 100 means one full core
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub uptime_seconds: u64,
+    pub restarts: u32,
+    /// TCP ports any process in the tree listens on
+    pub ports: Vec<u16>,
+}
+
+/// Keeps one `System` alive between samples so CPU usage is measured over the interval
+#[derive(Default)]
+pub struct MetricsCollector {
+    sys: Mutex<System>,
+}
+
+impl MetricsCollector {
+    /// Sample every tracked child, plus an `ollama serve` started outside the launcher.
+    /// `restarts` maps MCP server names to their supervisor restart count.
+    pub fn collect(&self, restarts: &BTreeMap<String, u32>) -> Vec<ProcessMetrics> {
+        let mut sys = self.sys.lock().unwrap();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new().with_cpu().with_memory().with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        let mut roots: Vec<(String, ChildKind, u32)> = registry()
+            .children()
+            .into_iter()
+            .map(|c| (c.name, c.kind, c.pid))
+            .collect();
+        if !roots.iter().any(|(_, kind, _)| *kind == ChildKind::Ollama) {
+            let external = sys.processes().iter().find(|(_, p)| {
+                p.name().to_string_lossy().to_lowercase().starts_with("ollama")
+                    && p.cmd().iter().any(|a| a == "serve")
+            });
+            if let Some((pid, _)) = external {
+                roots.push(("ollama serve".to_string(), ChildKind::Ollama, pid.as_u32()));
+            }
+        }
+
+        let listening = listeners();
+        roots
+            .into_iter()
+            .filter_map(|(name, kind, pid)| {
+                let root = sys.process(Pid::from_u32(pid))?;
+                let pids = process_tree(&sys, pid);
+                let members: Vec<_> = pids.iter().filter_map(|p| sys.process(Pid::from_u32(*p))).collect();
+                let mut ports: Vec<u16> =
+                    listening.iter().filter(|(_, owner)| pids.contains(owner)).map(|(port, _)| *port).collect();
+                ports.dedup();
+
+                Some(ProcessMetrics {
+                    restarts: if kind == ChildKind::McpServer { restarts.get(&name).copied().unwrap_or(0) } else { 0 },
+                    name,
+                    kind,
+                    pid,
+                    cpu_percent: members.iter().map(|p| p.cpu_usage()).sum(),
+                    memory_bytes: members.iter().map(|p| p.memory()).sum(),
+                    uptime_seconds: root.run_time(),
+                    pids,
+                    ports,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Emit `process-metrics` every few seconds for the lifetime of the app
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(STREAM_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<crate::commands::AppState>();
+            let restarts = state.mcp_restarts();
+            let metrics = state.metrics.clone();
+            let Ok(sample) = tokio::task::spawn_blocking(move || metrics.collect(&restarts)).await else {
+                continue;
+            };
+            let _ = app.emit("process-metrics", &sample);
+        }
+    });
+}
@@ -1,3 +1,6 @@
 pub mod claude;
+pub mod metrics;
 pub mod ollama;
+pub mod registry;
 pub mod session;
+pub mod shutdown;
@@ -2,8 +2,6 @@ use reqwest::Client;
 use serde::Deserialize;
 use std::time::Duration;
 
-const OLLAMA_URL: &str = "http://127.0.0.1:11434";
-
 #[derive(Debug, Deserialize)]
 struct OllamaTagsResponse {
     models: Vec<OllamaModel>,
@@ -14,28 +12,28 @@ struct OllamaModel {
     name: String,
 }
 
-/// Check if Ollama is running on default port
-pub async fn check_ollama_running() -> Result<bool, String> {
+/// Check if Ollama is running at the configured URL
+pub async fn check_ollama_running(base_url: &str) -> Result<bool, String> {
     let client = Client::builder()
         .timeout(Duration::from_secs(2))
         .build()
         .map_err(|e| e.to_string())?;
 
-    match client.get(format!("{}/api/tags", OLLAMA_URL)).send().await {
+    match client.get(format!("{}/api/tags", base_url)).send().await {
         Ok(response) => Ok(response.status().is_success()),
         Err(_) => Ok(false),
     }
 }
 
 /// Get list of available Ollama models
-pub async fn get_ollama_model_list() -> Result<Vec<String>, String> {
+pub async fn get_ollama_model_list(base_url: &str) -> Result<Vec<String>, String> {
     let client = Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
         .map_err(|e| e.to_string())?;
 
     let response = client
-        .get(format!("{}/api/tags", OLLAMA_URL))
+        .get(format!("{}/api/tags", base_url))
         .send()
         .await
         .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
@@ -52,39 +50,40 @@ pub async fn get_ollama_model_list() -> Result<Vec<String>, String> {
     Ok(tags.models.into_iter().map(|m| m.name).collect())
 }
 
-/// Start Ollama service (Windows)
-#[allow(dead_code)]
-#[cfg(windows)]
-pub async fn start_ollama() -> Result<(), String> {
-    use std::process::Command;
-
-    Command::new("cmd")
-        .args(["/c", "start", "ollama", "serve"])
-        .spawn()
-        .map_err(|e| format!("Failed to start Ollama: {}", e))?;
-
-    // Wait for Ollama to start
-    tokio::time::sleep(Duration::from_secs(3)).await;
-
-    if check_ollama_running().await? {
-        Ok(())
-    } else {
-        Err("Ollama started but not responding".to_string())
-    }
+/// Whether an `ollama serve` process is running, whoever started it
+pub fn ollama_serve_running() -> bool {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    sys.processes().values().any(|p| {
+        p.name().to_string_lossy().to_lowercase().starts_with("ollama") && p.cmd().iter().any(|a| a == "serve")
+    })
 }
 
-#[cfg(not(windows))]
-pub async fn start_ollama() -> Result<(), String> {
-    use std::process::Command;
-
-    Command::new("ollama")
-        .arg("serve")
-        .spawn()
-        .map_err(|e| format!("Failed to start Ollama: {}", e))?;
+/// Start `ollama serve` as a tracked child of the launcher
+pub async fn start_ollama(base_url: &str) -> Result<(), String> {
+    use super::registry::{track, ChildKind};
+
+    let mut cmd = tokio::process::Command::new("ollama");
+    cmd.arg("serve");
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        cmd.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
+    }
+    let child = cmd.spawn().map_err(|e| format!("Failed to start Ollama: {}", e))?;
+    track(child, ChildKind::Ollama, "ollama serve");
 
+    // Wait for Ollama to start
     tokio::time::sleep(Duration::from_secs(3)).await;
 
-    if check_ollama_running().await? {
+    if check_ollama_running(base_url).await? {
         Ok(())
     } else {
         Err("Ollama started but not responding".to_string())
@@ -0,0 +1,306 @@
+use super::shutdown::{terminate_tree, ShutdownReport};
+use crate::config::edit::write_atomic;
+use crate::logger::{log_error, log_info, log_warn};
+use crate::paths::HydraPaths;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// Grace period for children still running when the launcher exits
+const EXIT_GRACE: Duration = Duration::from_secs(3);
+
+/// What the launcher started a child for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChildKind {
+    McpServer,
+    ClaudeCli,
+    ClaudeSession,
+    Ollama,
+}
+
+/// A spawned child, identified by PID plus start time and command line so a recycled
+/// PID is never mistaken for it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChildRecord {
+    pub pid: u32,
+    pub kind: ChildKind,
+    pub name: String,
+    /// Command line as reported by the OS at registration
+    pub cmd: Vec<String>,
+    /// OS start time in seconds since the epoch; 0 when unknown
+    pub start_time: u64,
+    pub started_at: String,
+}
+
+/// One state file per launcher instance: `<state_dir>/children/<launcher pid>.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistryFile {
+    launcher_pid: u32,
+    launcher_start_time: u64,
+    children: Vec<ChildRecord>,
+    /// Leftovers of crashed runs the user has neither reaped nor adopted yet
+    orphans: Vec<ChildRecord>,
+}
+
+/// Every child process the launcher spawned, persisted so a crashed run can be cleaned up
+pub struct ChildRegistry {
+    dir: PathBuf,
+    pid: u32,
+    start_time: u64,
+    children: Mutex<BTreeMap<u32, ChildRecord>>,
+    orphans: Mutex<BTreeMap<u32, ChildRecord>>,
+}
+
+static REGISTRY: OnceLock<ChildRegistry> = OnceLock::new();
+
+/// Registry of this launcher, loading leftovers of crashed runs on first use
+pub fn registry() -> &'static ChildRegistry {
+    REGISTRY.get_or_init(|| ChildRegistry::open(&HydraPaths::resolve().state_dir.join("children")))
+}
+
+/// Register a detached child and reap it in the background, forgetting it once it exits
+pub fn track(mut child: tokio::process::Child, kind: ChildKind, name: &str) {
+    let Some(pid) = child.id() else {
+        return;
+    };
+    registry().register(pid, kind, name);
+    tauri::async_runtime::spawn(async move {
+        let _ = child.wait().await;
+        registry().unregister(pid);
+    });
+}
+
+fn inspect(pids: &[u32]) -> System {
+    let pids: Vec<Pid> = pids.iter().map(|p| Pid::from_u32(*p)).collect();
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::Always),
+    );
+    sys
+}
+
+fn cmd_line(process: &sysinfo::Process) -> Vec<String> {
+    process.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect()
+}
+
+/// Whether `record` still describes a live process
+fn verify(sys: &System, record: &ChildRecord) -> bool {
+    let Some(process) = sys.process(Pid::from_u32(record.pid)) else {
+        return false;
+    };
+    (record.start_time == 0 || process.start_time() == record.start_time)
+        && (record.cmd.is_empty() || cmd_line(process) == record.cmd)
+}
+
+fn launcher_alive(file: &RegistryFile) -> bool {
+    let sys = inspect(&[file.launcher_pid]);
+    sys.process(Pid::from_u32(file.launcher_pid))
+        .is_some_and(|p| p.start_time() == file.launcher_start_time)
+}
+
+impl ChildRegistry {
+    fn open(dir: &Path) -> Self {
+        let pid = std::process::id();
+        let start_time = inspect(&[pid]).process(Pid::from_u32(pid)).map(|p| p.start_time()).unwrap_or(0);
+        let registry = Self {
+            dir: dir.to_path_buf(),
+            pid,
+            start_time,
+            children: Mutex::new(BTreeMap::new()),
+            orphans: Mutex::new(BTreeMap::new()),
+        };
+        registry.absorb_leftovers();
+        registry
+    }
+
+    fn file_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.json", self.pid))
+    }
+
+    /// Take over the state files of launchers that are gone, keeping only children that
+    /// are verifiably still running
+    fn absorb_leftovers(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut candidates = Vec::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().map_or(true, |e| e != "json") || path == self.file_path() {
+                continue;
+            }
+            let file: RegistryFile = match fs::read_to_string(&path).map(|c| serde_json::from_str(&c)) {
+                Ok(Ok(file)) => file,
+                _ => {
+                    log_warn(&format!("Ignoring unreadable child registry: {}", path.display()));
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+            };
+            if launcher_alive(&file) {
+                continue;
+            }
+            candidates.extend(file.children.into_iter().chain(file.orphans));
+            let _ = fs::remove_file(&path);
+        }
+
+        let sys = inspect(&candidates.iter().map(|c| c.pid).collect::<Vec<_>>());
+        let mut orphans = self.orphans.lock().unwrap();
+        for record in candidates.into_iter().filter(|r| verify(&sys, r)) {
+            log_warn(&format!("Found orphaned {:?} '{}' (PID {}) from a previous run", record.kind, record.name, record.pid));
+            orphans.insert(record.pid, record);
+        }
+        drop(orphans);
+        self.persist();
+    }
+
+    fn persist(&self) {
+        let file = RegistryFile {
+            launcher_pid: self.pid,
+            launcher_start_time: self.start_time,
+            children: self.children.lock().unwrap().values().cloned().collect(),
+            orphans: self.orphans.lock().unwrap().values().cloned().collect(),
+        };
+        if file.children.is_empty() && file.orphans.is_empty() {
+            let _ = fs::remove_file(self.file_path());
+            return;
+        }
+        let content = serde_json::to_string_pretty(&file).unwrap_or_default();
+        if let Err(e) = write_atomic(&self.file_path(), &content) {
+            log_error(&format!("Failed to save child registry: {}", e));
+        }
+    }
+
+    /// Track a freshly spawned child, dropping records of children that exited unnoticed
+    pub fn register(&self, pid: u32, kind: ChildKind, name: &str) {
+        let mut children = self.children.lock().unwrap();
+        let mut pids: Vec<u32> = children.keys().copied().collect();
+        pids.push(pid);
+        let sys = inspect(&pids);
+        children.retain(|_, record| verify(&sys, record));
+
+        let process = sys.process(Pid::from_u32(pid));
+        let record = ChildRecord {
+            pid,
+            kind,
+            name: name.to_string(),
+            cmd: process.map(cmd_line).unwrap_or_default(),
+            start_time: process.map(|p| p.start_time()).unwrap_or(0),
+            started_at: Local::now().to_rfc3339(),
+        };
+        children.insert(pid, record);
+        drop(children);
+        self.persist();
+    }
+
+    /// Forget a child that exited or was stopped
+    pub fn unregister(&self, pid: u32) {
+        if self.children.lock().unwrap().remove(&pid).is_some() {
+            self.persist();
+        }
+    }
+
+    pub fn children(&self) -> Vec<ChildRecord> {
+        self.children.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn orphans(&self) -> Vec<ChildRecord> {
+        self.orphans.lock().unwrap().values().cloned().collect()
+    }
+
+    fn take_orphans(&self, pids: &[u32]) -> Result<Vec<ChildRecord>, String> {
+        let mut orphans = self.orphans.lock().unwrap();
+        if let Some(pid) = pids.iter().find(|p| !orphans.contains_key(p)) {
+            return Err(format!("Not an orphaned process: {}", pid));
+        }
+        Ok(pids.iter().filter_map(|p| orphans.remove(p)).collect())
+    }
+
+    /// Terminate orphans of a crashed run, re-checking each one right before signalling it
+    pub async fn reap(&self, pids: &[u32]) -> Result<Vec<ShutdownReport>, String> {
+        let records = self.take_orphans(pids)?;
+        self.persist();
+
+        let sys = inspect(pids);
+        let mut reports = Vec::new();
+        for record in records {
+            if !verify(&sys, &record) {
+                log_info(&format!("Orphaned '{}' (PID {}) already exited", record.name, record.pid));
+                continue;
+            }
+            let report = terminate_tree(record.pid, EXIT_GRACE).await;
+            log_info(&format!("Reaped orphaned '{}': PIDs {:?}", record.name, report.reaped));
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Keep orphans running and stop them with this launcher's children on exit
+    pub fn adopt(&self, pids: &[u32]) -> Result<Vec<ChildRecord>, String> {
+        let records = self.take_orphans(pids)?;
+        let mut children = self.children.lock().unwrap();
+        for record in &records {
+            log_info(&format!("Adopted orphaned '{}' (PID {})", record.name, record.pid));
+            children.insert(record.pid, record.clone());
+        }
+        drop(children);
+        self.persist();
+        Ok(records)
+    }
+
+    /// Stop every tracked child; called when the launcher exits
+    pub async fn shutdown(&self) {
+        let records = std::mem::take(&mut *self.children.lock().unwrap());
+        let sys = inspect(&records.keys().copied().collect::<Vec<_>>());
+        for record in records.into_values().filter(|r| verify(&sys, r)) {
+            let report = terminate_tree(record.pid, EXIT_GRACE).await;
+            log_info(&format!("Stopped {:?} '{}' on exit: PIDs {:?}", record.kind, record.name, report.reaped));
+        }
+        self.persist();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leftovers_are_verified_by_command_line() {
+        let dir = std::env::temp_dir().join(format!("hydra-children-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+
+        let crashed = ChildRegistry::open(&dir);
+        crashed.register(child.id(), ChildKind::McpServer, "serena");
+        let mut recycled = crashed.children()[0].clone();
+        recycled.pid = std::process::id();
+        // Pretend the crashed run's launcher is gone and it also recorded a PID that was reused
+        let file = RegistryFile {
+            launcher_pid: u32::MAX - 1,
+            launcher_start_time: 0,
+            children: vec![crashed.children()[0].clone(), recycled],
+            orphans: Vec::new(),
+        };
+        fs::write(dir.join("1.json"), serde_json::to_string(&file).unwrap()).unwrap();
+
+        let registry = ChildRegistry::open(&dir);
+        let orphans = registry.orphans();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].pid, child.id());
+        assert!(!dir.join("1.json").exists());
+
+        registry.adopt(&[child.id()]).unwrap();
+        assert!(registry.orphans().is_empty());
+        tauri::async_runtime::block_on(registry.shutdown());
+        assert!(child.wait().is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,17 +1,17 @@
+use std::collections::BTreeMap;
 use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use super::registry::{registry, ChildKind};
 
 /// Claude CLI Session Manager
-#[allow(dead_code)]
 pub struct ClaudeSession {
     process: Option<Child>,
     output_buffer: Arc<Mutex<Vec<String>>>,
     is_running: bool,
 }
 
-#[allow(dead_code)]
 impl ClaudeSession {
     pub fn new() -> Self {
         Self {
@@ -21,9 +21,15 @@ impl ClaudeSession {
         }
     }
 
-    /// Start a new Claude CLI session
-    pub fn start(&mut self, yolo_mode: bool, hydra_path: &str) -> Result<(), String> {
-        if self.is_running {
+    /// Start a new Claude CLI session from `program` with the config env applied
+    pub fn start(
+        &mut self,
+        program: &str,
+        yolo_mode: bool,
+        hydra_path: &str,
+        env: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        if self.is_running() {
             return Err("Session already running".to_string());
         }
 
@@ -35,15 +41,18 @@ impl ClaudeSession {
 
         // Start Claude CLI with piped stdin/stdout
         // Note: Claude CLI doesn't have --cwd option, we use current_dir() instead
-        let mut child = Command::new("claude")
+        let mut child = Command::new(program)
             .current_dir(hydra_path)
             .args(&args)
+            .envs(env)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stderr(Stdio::null())
             .spawn()
             .map_err(|e| format!("Failed to start Claude CLI: {}", e))?;
 
+        registry().register(child.id(), ChildKind::ClaudeSession, "claude");
+
         // Capture stdout in background thread
         let stdout = child.stdout.take();
         let buffer = Arc::clone(&self.output_buffer);
@@ -51,11 +60,9 @@ impl ClaudeSession {
         if let Some(stdout) = stdout {
             thread::spawn(move || {
                 let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let mut buf = buffer.lock().unwrap();
-                        buf.push(line);
-                    }
+                for line in reader.lines().map_while(Result::ok) {
+                    let mut buf = buffer.lock().unwrap();
+                    buf.push(line);
                 }
             });
         }
@@ -95,13 +102,23 @@ impl ClaudeSession {
     pub fn stop(&mut self) -> Result<(), String> {
         if let Some(ref mut child) = self.process {
             child.kill().map_err(|e| format!("Failed to kill process: {}", e))?;
+            let _ = child.wait();
+            registry().unregister(child.id());
         }
         self.process = None;
         self.is_running = false;
         Ok(())
     }
 
-    pub fn is_running(&self) -> bool {
+    /// Whether the session process is still alive
+    pub fn is_running(&mut self) -> bool {
+        if let Some(ref mut child) = self.process {
+            if !matches!(child.try_wait(), Ok(None)) {
+                registry().unregister(child.id());
+                self.process = None;
+                self.is_running = false;
+            }
+        }
         self.is_running
     }
 }
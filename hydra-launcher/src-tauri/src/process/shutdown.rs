@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+
+/// How often liveness is re-checked while waiting for processes to exit
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to wait for the kernel to clean up after a forced kill
+const KILL_WAIT: Duration = Duration::from_secs(2);
+
+/// Outcome of stopping a process tree
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ShutdownReport {
+    /// Root process of the tree (the group leader)
+    pub pid: u32,
+    /// Every PID that exited, including the root
+    pub reaped: Vec<u32>,
+    /// PIDs that ignored the graceful signal and were killed after the grace period
+    pub killed: Vec<u32>,
+    /// PIDs still alive after the forced kill
+    pub remaining: Vec<u32>,
+}
+
+/// PID of `root` and all of its descendants
+pub fn process_tree(sys: &System, root: u32) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = Pid::from_u32(tree[i]);
+        for (pid, process) in sys.processes() {
+            if process.parent() == Some(parent) && !tree.contains(&pid.as_u32()) {
+                tree.push(pid.as_u32());
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+fn snapshot() -> System {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new());
+    sys
+}
+
+/// PIDs of `pids` that are still running; zombies count as exited
+fn alive(pids: &[u32]) -> Vec<u32> {
+    let sys = snapshot();
+    pids.iter()
+        .copied()
+        .filter(|pid| sys.process(Pid::from_u32(*pid)).is_some_and(|p| p.status() != ProcessStatus::Zombie))
+        .collect()
+}
+
+async fn wait_for_exit(pids: &[u32], limit: Duration) -> Vec<u32> {
+    let deadline = Instant::now() + limit;
+    loop {
+        let pids = pids.to_vec();
+        let remaining = tokio::task::spawn_blocking(move || alive(&pids)).await.unwrap_or_default();
+        if remaining.is_empty() || Instant::now() >= deadline {
+            return remaining;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: i32, members: &[u32]) {
+    // Supervised servers lead their own group, so the group id is the root PID
+    unsafe {
+        if libc::kill(-(pid as i32), signal) != 0 {
+            libc::kill(pid as i32, signal);
+        }
+        // Descendants that moved to another group still get the signal
+        for member in members {
+            libc::kill(*member as i32, signal);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn taskkill(pid: u32, force: bool) {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let mut cmd = std::process::Command::new("taskkill");
+    cmd.args(["/T", "/PID", &pid.to_string()]);
+    if force {
+        cmd.arg("/F");
+    }
+    let _ = cmd.creation_flags(CREATE_NO_WINDOW).output();
+}
+
+/// Ask the process group led by `pid` to exit (SIGTERM / `taskkill /T`), wait up to
+/// `grace`, then force-kill whatever is left (SIGKILL / `taskkill /T /F`)
+pub async fn terminate_tree(pid: u32, grace: Duration) -> ShutdownReport {
+    let tree = tokio::task::spawn_blocking(move || process_tree(&snapshot(), pid))
+        .await
+        .unwrap_or_else(|_| vec![pid]);
+
+    #[cfg(unix)]
+    signal_group(pid, libc::SIGTERM, &tree[1..]);
+    #[cfg(windows)]
+    taskkill(pid, false);
+
+    let survivors = wait_for_exit(&tree, grace).await;
+    let remaining = if survivors.is_empty() {
+        survivors.clone()
+    } else {
+        #[cfg(unix)]
+        signal_group(pid, libc::SIGKILL, &survivors);
+        #[cfg(windows)]
+        {
+            taskkill(pid, true);
+            for survivor in &survivors {
+                taskkill(*survivor, true);
+            }
+        }
+        wait_for_exit(&survivors, KILL_WAIT).await
+    };
+
+    ShutdownReport {
+        pid,
+        reaped: tree.iter().copied().filter(|p| !remaining.contains(p)).collect(),
+        killed: survivors.into_iter().filter(|p| !remaining.contains(p)).collect(),
+        remaining,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+
+    #[tokio::test]
+    async fn test_terminate_tree_reaps_group() {
+        // A shell that ignores SIGTERM with a grandchild, like npx -> node
+        let mut child = std::process::Command::new("sh")
+            .args(["-c", "trap '' TERM; sleep 30 & wait"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let report = terminate_tree(child.id(), Duration::from_millis(300)).await;
+        let _ = child.wait();
+        assert!(report.remaining.is_empty(), "{:?}", report);
+        assert!(report.reaped.len() >= 2, "{:?}", report);
+        assert!(report.killed.contains(&child.id()), "{:?}", report);
+    }
+}
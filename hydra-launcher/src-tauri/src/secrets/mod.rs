@@ -0,0 +1,152 @@
+pub mod vault;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::paths::HydraPaths;
+use vault::{SecretEntry, Vault};
+
+/// Config value that points at a secret instead of containing it
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretRef<'a> {
+    /// `secret://name` - entry in the local vault
+    Vault(&'a str),
+    /// `env://NAME` - process environment variable
+    Env(&'a str),
+    /// `file://path` - file contents; relative paths are under the config dir
+    File(&'a str),
+}
+
+impl<'a> SecretRef<'a> {
+    pub fn parse(value: &'a str) -> Option<Self> {
+        if let Some(name) = value.strip_prefix("secret://") {
+            Some(Self::Vault(name))
+        } else if let Some(name) = value.strip_prefix("env://") {
+            Some(Self::Env(name))
+        } else {
+            value.strip_prefix("file://").map(Self::File)
+        }
+    }
+}
+
+/// Masked view of a vault entry for the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretSummary {
+    pub name: String,
+    pub masked: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SecretSummary {
+    pub fn new(name: &str, entry: &SecretEntry) -> Self {
+        Self {
+            name: name.to_string(),
+            masked: mask(&entry.value),
+            created_at: entry.created_at.clone(),
+            updated_at: entry.updated_at.clone(),
+        }
+    }
+}
+
+/// Show only the last 4 characters of long values
+pub fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        "********".to_string()
+    } else {
+        format!("****{}", chars[chars.len() - 4..].iter().collect::<String>())
+    }
+}
+
+/// Secret names are used in `secret://` URLs
+pub fn validate_secret_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        Ok(())
+    } else {
+        Err(format!("Invalid secret name `{}`: use letters, digits, '-', '_' or '.'", name))
+    }
+}
+
+/// Vault entries decrypted on first use, so a whole env block costs one key derivation
+struct Unlocked<'a> {
+    vault: &'a Vault,
+    entries: Option<BTreeMap<String, SecretEntry>>,
+}
+
+impl<'a> Unlocked<'a> {
+    fn new(vault: &'a Vault) -> Self {
+        Self { vault, entries: None }
+    }
+
+    fn get(&mut self, name: &str) -> Result<Option<SecretEntry>, String> {
+        if self.entries.is_none() {
+            self.entries = Some(self.vault.entries()?);
+        }
+        Ok(self.entries.as_ref().and_then(|entries| entries.get(name)).cloned())
+    }
+}
+
+/// Resolve a config value if it is a secret reference; other values are returned as-is
+fn resolve_with(value: &str, vault: &mut Unlocked, env: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    match SecretRef::parse(value) {
+        None => Ok(value.to_string()),
+        Some(SecretRef::Vault(name)) => vault
+            .get(name)?
+            .map(|entry| entry.value)
+            .ok_or_else(|| format!("Secret not found in vault: {}", name)),
+        Some(SecretRef::Env(name)) => env(name).ok_or_else(|| format!("Environment variable not set: {}", name)),
+        Some(SecretRef::File(path)) => {
+            let path = PathBuf::from(path);
+            let path = if path.is_relative() { HydraPaths::resolve().config_dir.join(path) } else { path };
+            std::fs::read_to_string(&path)
+                .map(|content| content.trim_end_matches(['\r', '\n']).to_string())
+                .map_err(|e| format!("Failed to read secret file {}: {}", path.display(), e))
+        }
+    }
+}
+
+/// Resolve every reference in an env block, for handing to a child process.
+/// The result must not be logged or written back to the config.
+pub fn resolve_env(env: &BTreeMap<String, String>) -> Result<BTreeMap<String, String>, String> {
+    let vault = Vault::open_default();
+    let mut unlocked = Unlocked::new(&vault);
+    env.iter()
+        .map(|(key, value)| {
+            resolve_with(value, &mut unlocked, |name| std::env::var(name).ok())
+                .map(|v| (key.clone(), v))
+                .map_err(|e| format!("env.{}: {}", key, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_round_trip_and_references() {
+        let dir = std::env::temp_dir().join(format!("hydra-secrets-{}", std::process::id()));
+        let path = dir.join("secrets.vault");
+        let vault = Vault::with_passphrase(path.clone(), "correct horse");
+
+        vault.add("anthropic", "sk-ant-0123456789abcd").unwrap();
+        assert!(vault.add("anthropic", "again").is_err());
+        vault.rotate("anthropic", "sk-ant-rotated-5678wxyz").unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("sk-ant"));
+
+        let env = |name: &str| (name == "OPENAI_KEY").then(|| "sk-env".to_string());
+        let mut unlocked = Unlocked::new(&vault);
+        assert_eq!(resolve_with("secret://anthropic", &mut unlocked, env).unwrap(), "sk-ant-rotated-5678wxyz");
+        assert_eq!(resolve_with("env://OPENAI_KEY", &mut unlocked, env).unwrap(), "sk-env");
+        assert_eq!(resolve_with("plain", &mut unlocked, env).unwrap(), "plain");
+        assert!(resolve_with("secret://missing", &mut unlocked, env).is_err());
+        assert_eq!(mask(&vault.entries().unwrap()["anthropic"].value), "****wxyz");
+
+        let wrong = Vault::with_passphrase(path, "wrong");
+        let err = wrong.entries().unwrap_err();
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(err.contains("wrong passphrase"), "{}", err);
+    }
+}
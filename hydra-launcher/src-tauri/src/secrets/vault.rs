@@ -0,0 +1,214 @@
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::edit::write_atomic;
+use crate::paths::HydraPaths;
+
+/// Passphrase env var; when unset the vault key lives in the OS keyring
+pub const PASSPHRASE_VAR: &str = "HYDRA_VAULT_PASSPHRASE";
+
+const VAULT_FORMAT: u32 = 1;
+const KEYRING_SERVICE: &str = "hydra-launcher";
+const KEYRING_USER: &str = "vault-key";
+
+/// Serializes read-modify-write cycles on the vault file
+static VAULT_LOCK: Mutex<()> = Mutex::new(());
+
+/// How the vault encryption key is obtained
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// Argon2id over `HYDRA_VAULT_PASSPHRASE`
+    Passphrase,
+    /// Random key stored in the OS keyring (Credential Manager, Keychain, kernel keyring)
+    Keyring,
+}
+
+/// A stored secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretEntry {
+    pub value: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// On-disk envelope; only the entry map is encrypted
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    format: u32,
+    key_source: KeySource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// XChaCha20-Poly1305 encrypted secrets file
+pub struct Vault {
+    path: PathBuf,
+    passphrase: Option<String>,
+}
+
+impl Vault {
+    /// `<data_dir>/secrets.vault`, keyed by the passphrase env var or the OS keyring
+    pub fn open_default() -> Self {
+        Self {
+            path: HydraPaths::resolve().data_dir.join("secrets.vault"),
+            passphrase: std::env::var(PASSPHRASE_VAR).ok().filter(|p| !p.is_empty()),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_passphrase(path: PathBuf, passphrase: &str) -> Self {
+        Self { path, passphrase: Some(passphrase.to_string()) }
+    }
+
+    pub fn entries(&self) -> Result<BTreeMap<String, SecretEntry>, String> {
+        self.read()
+    }
+
+    /// Store a new secret; fails if the name is taken
+    pub fn add(&self, name: &str, value: &str) -> Result<SecretEntry, String> {
+        self.modify(|entries| {
+            if entries.contains_key(name) {
+                return Err(format!("Secret already exists: {}", name));
+            }
+            let now = Local::now().to_rfc3339();
+            let entry = SecretEntry { value: value.to_string(), created_at: now.clone(), updated_at: now };
+            entries.insert(name.to_string(), entry.clone());
+            Ok(entry)
+        })
+    }
+
+    /// Replace the value of an existing secret
+    pub fn rotate(&self, name: &str, value: &str) -> Result<SecretEntry, String> {
+        self.modify(|entries| {
+            let entry = entries.get_mut(name).ok_or_else(|| format!("Secret not found: {}", name))?;
+            entry.value = value.to_string();
+            entry.updated_at = Local::now().to_rfc3339();
+            Ok(entry.clone())
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        self.modify(|entries| {
+            entries.remove(name).map(|_| ()).ok_or_else(|| format!("Secret not found: {}", name))
+        })
+    }
+
+    fn modify<T>(
+        &self,
+        f: impl FnOnce(&mut BTreeMap<String, SecretEntry>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let _guard = VAULT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = self.read()?;
+        let result = f(&mut entries)?;
+        self.write(&entries)?;
+        Ok(result)
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, SecretEntry>, String> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| format!("Failed to read vault: {}", e))?;
+        let file: VaultFile = serde_json::from_str(&content).map_err(|e| format!("Vault file is corrupted: {}", e))?;
+        if file.format != VAULT_FORMAT {
+            return Err(format!("Unsupported vault format {}", file.format));
+        }
+
+        let salt = file.salt.as_deref().map(decode).transpose()?;
+        let key = self.key(file.key_source, salt.as_deref())?;
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != 24 {
+            return Err("Vault file is corrupted: bad nonce".to_string());
+        }
+        let plaintext = XChaCha20Poly1305::new(&key.into())
+            .decrypt(XNonce::from_slice(&nonce), decode(&file.ciphertext)?.as_slice())
+            .map_err(|_| "Failed to unlock vault: wrong passphrase or corrupted file".to_string())?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Vault contents are corrupted: {}", e))
+    }
+
+    /// Re-encrypts with the current key source, so setting a passphrase re-keys a keyring vault
+    fn write(&self, entries: &BTreeMap<String, SecretEntry>) -> Result<(), String> {
+        let key_source = if self.passphrase.is_some() { KeySource::Passphrase } else { KeySource::Keyring };
+        let salt = (key_source == KeySource::Passphrase).then(|| {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            salt
+        });
+        let key = self.key(key_source, salt.as_ref().map(|s| s.as_slice()))?;
+
+        let plaintext = serde_json::to_vec(entries).map_err(|e| format!("Failed to serialize vault: {}", e))?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&key.into())
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt vault".to_string())?;
+
+        let file = VaultFile {
+            format: VAULT_FORMAT,
+            key_source,
+            salt: salt.map(|s| BASE64.encode(s)),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let content = serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize vault: {}", e))?;
+        write_atomic(&self.path, &content)
+    }
+
+    fn key(&self, source: KeySource, salt: Option<&[u8]>) -> Result<[u8; 32], String> {
+        match source {
+            KeySource::Passphrase => {
+                let passphrase = self
+                    .passphrase
+                    .as_deref()
+                    .ok_or_else(|| format!("Vault is passphrase-protected: set {}", PASSPHRASE_VAR))?;
+                let salt = salt.ok_or("Vault file is corrupted: missing salt")?;
+                let mut key = [0u8; 32];
+                Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| format!("Failed to derive vault key: {}", e))?;
+                Ok(key)
+            }
+            KeySource::Keyring => keyring_key(),
+        }
+    }
+}
+
+/// Vault key from the OS keyring, created on first use
+fn keyring_key() -> Result<[u8; 32], String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("OS keyring unavailable ({}); set {} instead", e, PASSPHRASE_VAR))?;
+
+    let encoded = match entry.get_password() {
+        Ok(encoded) => encoded,
+        Err(keyring::Error::NoEntry) => {
+            let mut key = [0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            let encoded = BASE64.encode(key);
+            entry
+                .set_password(&encoded)
+                .map_err(|e| format!("Failed to store vault key in OS keyring: {}", e))?;
+            encoded
+        }
+        Err(e) => return Err(format!("OS keyring unavailable ({}); set {} instead", e, PASSPHRASE_VAR)),
+    };
+
+    decode(&encoded)?
+        .try_into()
+        .map_err(|_| "Vault key in OS keyring is corrupted".to_string())
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, String> {
+    BASE64.decode(s).map_err(|e| format!("Vault file is corrupted: {}", e))
+}
@@ -145,7 +145,7 @@ const Launcher: React.FC = () => {
           isLight ? 'text-amber-700/50' : 'text-amber-500/40'
         }`}
       >
-        ◆ HYDRA 10.4 ◆
+        ◆ HYDRA 10.5 ◆
       </div>
     </div>
   );
@@ -120,7 +120,7 @@ const ServerRow: React.FC<{
             {server.name}
           </div>
           <div className={`text-[10px] font-cinzel ${isLight ? 'text-amber-600/60' : 'text-amber-500/50'}`}>
-            Port {server.port}
+            {server.port !== null ? `Port ${server.port}` : server.error}
           </div>
         </div>
       </div>
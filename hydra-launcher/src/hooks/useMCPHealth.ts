@@ -1,22 +1,43 @@
 import { useEffect, useState, useCallback } from "react";
-import { safeInvoke, isTauri } from "./useTauri";
+import { safeInvoke, safeListen, isTauri } from "./useTauri";
 
 export interface McpHealthResult {
   name: string;
-  port: number;
-  status: "online" | "offline" | "error";
+  port: number | null;
+  strategy: "port" | "http" | "process" | "mcp";
+  status: "online" | "degraded" | "offline" | "error" | "stopped";
   response_time_ms: number | null;
   error: string | null;
+  handshake?: McpHandshake | null;
+}
+
+export interface McpHandshake {
+  transport: "stdio" | "sse" | "http";
+  protocol_version: string;
+  server_name: string;
+  server_version: string | null;
+  capabilities: Record<string, unknown>;
+  ping_ms: number;
+}
+
+export interface McpStatusChange {
+  name: string;
+  previous: McpHealthResult["status"] | null;
+  was_slow: boolean;
+  slow: boolean;
+  result: McpHealthResult;
 }
 
 // Mock data for browser development
 const MOCK_MCP_HEALTH: McpHealthResult[] = [
-  { name: "Serena", port: 9000, status: "online", response_time_ms: 12, error: null },
-  { name: "Desktop Commander", port: 8100, status: "online", response_time_ms: 8, error: null },
-  { name: "Playwright", port: 5200, status: "offline", response_time_ms: null, error: "Not running" },
+  { name: "serena", port: 9000, strategy: "mcp", status: "online", response_time_ms: 12, error: null },
+  { name: "desktop-commander", port: 8100, strategy: "mcp", status: "online", response_time_ms: 8, error: null },
+  { name: "playwright", port: 5200, strategy: "mcp", status: "offline", response_time_ms: null, error: "Not running" },
 ];
 
-export function useMCPHealth(refreshInterval = 5000) {
+// The backend monitor checks each server on its own interval and emits
+// `mcp-status-changed` on transitions, so there is no polling here
+export function useMCPHealth() {
   const [health, setHealth] = useState<McpHealthResult[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -46,11 +67,40 @@ export function useMCPHealth(refreshInterval = 5000) {
   }, []);
 
   useEffect(() => {
-    checkHealth();
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+
+    const start = async () => {
+      if (!isTauri()) {
+        checkHealth();
+        return;
+      }
+      unlisten = await safeListen<McpStatusChange>("mcp-status-changed", (change) => {
+        setHealth((current) => {
+          const rest = current.filter((h) => h.name !== change.name);
+          return [...rest, change.result].sort((a, b) => a.name.localeCompare(b.name));
+        });
+      });
+      if (cancelled) {
+        unlisten();
+        return;
+      }
+      // Last known state; before the monitor's first round this is empty, so check now
+      const known = await safeInvoke<McpHealthResult[]>("get_mcp_status").catch(() => []);
+      if (known.length > 0) {
+        setHealth(known);
+        setIsLoading(false);
+      } else {
+        checkHealth();
+      }
+    };
+    start();
 
-    const interval = setInterval(checkHealth, refreshInterval);
-    return () => clearInterval(interval);
-  }, [checkHealth, refreshInterval]);
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [checkHealth]);
 
   const allOnline = health.every((h) => h.status === "online");
   const onlineCount = health.filter((h) => h.status === "online").length;
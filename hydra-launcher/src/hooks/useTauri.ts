@@ -16,3 +16,13 @@ export async function safeInvoke<T>(command: string, args?: Record<string, unkno
   const { invoke } = await import('@tauri-apps/api/core');
   return invoke<T>(command, args);
 }
+
+// Safe event listener - resolves to a no-op unsubscribe in browser mode
+export async function safeListen<T>(event: string, handler: (payload: T) => void): Promise<() => void> {
+  if (!isTauri()) {
+    return () => {};
+  }
+
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<T>(event, (e) => handler(e.payload));
+}